@@ -0,0 +1,156 @@
+use std::mem::take;
+use crate::time::{Instant, Duration};
+use super::*;
+
+
+// animation registry, advanced before each RedrawRequested-event is handled
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AnimationId(u64);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationFrame {
+  pub id: AnimationId,
+  pub start: Instant, // frame time of the first frame
+  pub frame_time: Instant,
+  pub elapsed: Duration,
+  pub progress: Option<f32>, // 0..=1 for animations with a duration
+}
+
+impl AnimationFrame {
+  pub fn is_last(&self) -> bool { self.progress == Some(1.0) }
+}
+
+// returns true when done
+type AnimationFn<U> = Box<dyn FnMut(&mut AppCtx<U>, &AnimationFrame) -> bool>;
+
+struct Animation<U: EventLike> {
+  id: AnimationId,
+  start: Option<Instant>,
+  duration: Option<Duration>,
+  update: AnimationFn<U>,
+  #[cfg(feature = "app_clock")] app_time: bool, // driven by the app clock
+}
+
+
+pub(super) struct Animations<U: EventLike> {
+  running: Vec<Animation<U>>,
+  cancelled: Vec<AnimationId>, // cancelled while advancing
+  advancing: bool,
+  next_id: u64,
+}
+
+impl<U: EventLike> Animations<U> {
+
+  pub fn new() -> Self {
+    Self { running: Vec::new(), cancelled: Vec::new(), advancing: false, next_id: 0 }
+  }
+
+  pub fn advance(app_ctx: &mut AppCtx<U>) {
+
+    // take animations out, so that they may access the app_ctx freely
+    let mut running = take(&mut app_ctx.animations.running);
+
+    if running.is_empty() { return }
+
+    let frame_time = app_ctx.frame_time;
+    #[cfg(feature = "app_clock")] let app_frame_time = app_ctx.app_frame_time();
+    app_ctx.animations.advancing = true;
+
+    running.retain_mut(|animation| {
+
+      if app_ctx.animations.cancelled.contains(&animation.id) {
+        return false;
+      }
+
+      #[cfg(feature = "app_clock")]
+      let frame_time = if animation.app_time { app_frame_time } else { frame_time };
+
+      let start = *animation.start.get_or_insert(frame_time);
+      let elapsed = frame_time.saturating_duration_since(start);
+
+      let progress = animation.duration.map(|duration| {
+        if duration.is_zero() { 1.0 } else { (elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.0) }
+      });
+
+      let frame = AnimationFrame { id: animation.id, start, frame_time, elapsed, progress };
+
+      let done = (animation.update)(app_ctx, &frame);
+
+      !done && !frame.is_last()
+    });
+
+    let animations = &mut app_ctx.animations;
+
+    animations.advancing = false;
+    running.retain(|animation| !animations.cancelled.contains(&animation.id));
+    animations.cancelled.clear();
+
+    // merge with animations started while advancing
+    running.append(&mut animations.running);
+    animations.running = running;
+
+    if app_ctx.animations.needs_frame(app_ctx) { app_ctx.request_frame() }
+  }
+
+  // app-time animations do not need frames while the clock is paused
+  fn needs_frame(&self, app_ctx: &AppCtx<U>) -> bool {
+    #[cfg(feature = "app_clock")]
+    if app_ctx.is_clock_paused() { return self.running.iter().any(|animation| !animation.app_time) }
+    #[cfg(not(feature = "app_clock"))] let _ = app_ctx;
+    !self.running.is_empty()
+  }
+}
+
+
+// app-ctx interface
+
+impl<U: EventLike> AppCtx<U> {
+
+  fn add_animation(&mut self, duration: Option<Duration>, app_time: bool, update: AnimationFn<U>) -> AnimationId {
+    #[cfg(not(feature = "app_clock"))] let _ = app_time;
+    let id = AnimationId(self.animations.next_id);
+    self.animations.next_id = self.animations.next_id.wrapping_add(1);
+    self.animations.running.push(Animation {
+      id, start: None, duration, update,
+      #[cfg(feature = "app_clock")] app_time,
+    });
+    self.request_frame();
+    id
+  }
+
+  // called each frame with the progress, the last time with progress 1
+  pub fn animate(&mut self, duration: Duration, mut update: impl FnMut(&mut AppCtx<U>, &AnimationFrame) + 'static) -> AnimationId {
+    self.add_animation(Some(duration), false, Box::new(move |app_ctx, frame| { update(app_ctx, frame); false }))
+  }
+
+  // like animate, but the frame times are app instants, freezing while the app clock is paused
+  #[cfg(feature = "app_clock")]
+  pub fn animate_app_time(&mut self, duration: Duration, mut update: impl FnMut(&mut AppCtx<U>, &AnimationFrame) + 'static) -> AnimationId {
+    self.add_animation(Some(duration), true, Box::new(move |app_ctx, frame| { update(app_ctx, frame); false }))
+  }
+
+  // called each frame until it returns true
+  pub fn animate_until(&mut self, update: impl FnMut(&mut AppCtx<U>, &AnimationFrame) -> bool + 'static) -> AnimationId {
+    self.add_animation(None, false, Box::new(update))
+  }
+
+  #[cfg(feature = "app_clock")]
+  pub fn animate_until_app_time(&mut self, update: impl FnMut(&mut AppCtx<U>, &AnimationFrame) -> bool + 'static) -> AnimationId {
+    self.add_animation(None, true, Box::new(update))
+  }
+
+  pub fn cancel_animation(&mut self, id: AnimationId) {
+    let animations = &mut self.animations;
+    if let Some(i) = animations.running.iter().position(|animation| animation.id == id) {
+      animations.running.remove(i);
+    }
+    else if animations.advancing {
+      animations.cancelled.push(id);
+    }
+  }
+
+  pub fn is_animating(&self) -> bool { !self.animations.running.is_empty() || self.animations.advancing }
+
+  pub fn animation_count(&self) -> usize { self.animations.running.len() }
+}
@@ -0,0 +1,166 @@
+use crate::time::{Instant, Duration};
+use crate::timer::AppClock;
+use super::*;
+
+
+// app-clock feature, app time pauses while suspended and can be slowed down or sped up
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClockPausePolicy {
+  pub suspended: bool,
+  pub unfocused: bool,
+}
+
+impl Default for ClockPausePolicy {
+  fn default() -> Self { Self { suspended: true, unfocused: false } }
+}
+
+
+#[derive(Debug)]
+pub(super) struct ClockState {
+  pub(super) clock: AppClock,
+  policy: ClockPausePolicy,
+  paused: bool, // paused manually
+  suspended: bool,
+  unfocused: bool,
+  #[cfg(feature = "timeout")] timeouts: Vec<(u64, Duration)>,
+}
+
+impl ClockState {
+
+  pub(super) fn new() -> Self {
+    Self {
+      clock: AppClock::new(), policy: ClockPausePolicy::default(),
+      paused: false, suspended: false, unfocused: false,
+      #[cfg(feature = "timeout")] timeouts: Vec::new(),
+    }
+  }
+
+  fn should_pause(&self) -> bool {
+    self.paused || (self.policy.suspended && self.suspended) || (self.policy.unfocused && self.unfocused)
+  }
+
+  #[cfg(feature = "timeout")]
+  fn remove_timeout(&mut self, id: u64) {
+    self.timeouts.retain(|(other, _)| *other != id);
+  }
+}
+
+
+impl<U: EventLike> AppCtx<U> {
+
+  pub fn clock(&self) -> &AppClock { &self.clock.clock }
+
+  // elapsed app time
+  pub fn app_time(&self) -> Duration { self.clock.clock.elapsed() }
+
+  // current app instant, e.g. for NormInterval or StepInterval
+  pub fn app_now(&self) -> Instant { self.clock.clock.now() }
+
+  // the frame time as app instant
+  #[cfg(feature = "frame_pacing")]
+  pub fn app_frame_time(&self) -> Instant { self.clock.clock.instant_at(self.frame_time) }
+
+  pub fn is_clock_paused(&self) -> bool { self.clock.clock.is_paused() }
+
+  pub fn pause_clock(&mut self) {
+    self.clock.paused = true;
+    self.update_clock();
+  }
+
+  // the clock stays paused while the pause policy applies
+  pub fn resume_clock(&mut self) {
+    self.clock.paused = false;
+    self.update_clock();
+  }
+
+  pub fn clock_scale(&self) -> f64 { self.clock.clock.scale() }
+
+  pub fn set_clock_scale(&mut self, scale: f64) {
+    self.clock.clock.set_scale(scale);
+    self.clock_changed();
+  }
+
+  pub fn clock_pause_policy(&self) -> &ClockPausePolicy { &self.clock.policy }
+
+  pub fn set_clock_pause_policy(&mut self, policy: ClockPausePolicy) {
+    self.clock.policy = policy;
+    self.update_clock();
+  }
+
+  pub(super) fn clock_suspended(&mut self, suspended: bool) {
+    self.clock.suspended = suspended;
+    self.update_clock();
+  }
+
+  pub(super) fn clock_focused(&mut self, focused: bool) {
+    self.clock.unfocused = !focused;
+    self.update_clock();
+  }
+
+  fn update_clock(&mut self) {
+    let paused = self.clock.should_pause();
+    if paused != self.clock.clock.is_paused() {
+      self.clock.clock.set_paused_at(paused, Instant::now());
+      self.clock_changed();
+    }
+  }
+
+  fn clock_changed(&mut self) {
+
+    #[cfg(feature = "timeout")]
+    for (id, elapsed) in self.clock.timeouts.clone() {
+      let _ = self.schedule_app_timeout(id, elapsed);
+    }
+
+    // app-time animations stop requesting frames while paused
+    #[cfg(feature = "animations")]
+    if !self.is_clock_paused() && self.is_animating() { self.request_frame() }
+  }
+}
+
+
+// app-time timeouts, frozen while the clock is paused, dispatched as Event::AppTimeout
+
+#[cfg(feature = "timeout")]
+impl<U: EventLike> AppCtx<U> {
+
+  pub fn get_app_timeout(&self, id: u64) -> Option<Duration> {
+    self.clock.timeouts.iter().find_map(|(other, elapsed)| (*other == id).then_some(*elapsed))
+  }
+
+  pub fn set_app_timeout(&mut self, id: u64, elapsed: Duration) -> TimeoutResult {
+    self.clock.remove_timeout(id);
+    self.clock.timeouts.push((id, elapsed));
+    self.schedule_app_timeout(id, elapsed)
+  }
+
+  pub fn set_app_timeout_wait(&mut self, id: u64, duration: Duration) -> TimeoutResult {
+    let elapsed = self.app_time().saturating_add(duration);
+    self.set_app_timeout(id, elapsed)
+  }
+
+  pub fn cancel_app_timeout(&mut self, id: u64) -> TimeoutResult {
+    self.clock.remove_timeout(id);
+    self.timer.borrow_mut().cancel_timeout(&AppTimeoutId::AppClock(id), None)
+  }
+
+  fn schedule_app_timeout(&mut self, id: u64, elapsed: Duration) -> TimeoutResult {
+    let mut timer = self.timer.borrow_mut();
+    match self.clock.clock.wall_instant(elapsed, Instant::now()) {
+      Some(instant) => timer.set_timeout(AppTimeoutId::AppClock(id), instant),
+      None => timer.cancel_timeout(&AppTimeoutId::AppClock(id), None),
+    }
+  }
+
+  // returns the app time of the timeout when due, reschedules it otherwise
+  pub(super) fn app_timeout_fired(&mut self, id: u64) -> Option<Duration> {
+    let elapsed = self.get_app_timeout(id)?;
+    if self.app_time() < elapsed {
+      let _ = self.schedule_app_timeout(id, elapsed);
+      return None;
+    }
+    self.clock.remove_timeout(id);
+    Some(elapsed)
+  }
+}
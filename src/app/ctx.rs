@@ -46,9 +46,20 @@ pub struct AppCtx<U: EventLike = !> {
   #[cfg(feature = "frame_pacing")] manual_frame_duration: Option<Duration>,
   #[cfg(feature = "frame_pacing")] monitor_frame_duration: Option<Duration>,
   #[cfg(feature = "frame_pacing")] pub(super) frame_duration: Duration,
+  #[cfg(feature = "frame_pacing")] target_frame_duration: Duration, // throttled by power saving
+  #[cfg(feature = "frame_pacing")] pub(super) frame_stats: crate::timer::FrameStats,
+  #[cfg(feature = "power_saving")] pub(super) power: PowerSaving,
+  #[cfg(feature = "animations")] pub(super) animations: Animations<U>,
+  #[cfg(feature = "app_clock")] pub(super) clock: ClockState,
+  #[cfg(feature = "monitor_change")] pub(super) monitor: MonitorTracker,
 
   pub(super) event_dispatcher: AppCtxEventDispatcher<U>,
 
+  #[cfg(feature = "event_bus")] pub(super) event_bus: EventBus<U>,
+  #[cfg(feature = "screens")] pub(super) screens: ScreenStack<U>,
+  #[cfg(feature = "input")] pub(super) input: crate::input::InputState,
+  #[cfg(feature = "regions")] pub(super) regions: Regions,
+
   #[allow(unused)] window_id: WindowId,
 
   pub(super) window: Arc<Window>,
@@ -60,6 +71,13 @@ impl<U: EventLike> AppCtx<U> {
 
   pub fn window_clone(&self) -> Arc<Window> { self.window.clone() }
 
+  #[cfg(feature = "input")]
+  pub fn input(&self) -> &crate::input::InputState { &self.input }
+
+  // consumes the scroll accumulated since the last call
+  #[cfg(feature = "input")]
+  pub fn take_scroll(&mut self) -> crate::input::ScrollDelta { self.input.take_scroll() }
+
   pub fn new(futures: AppFutureRuntime<U>, timer: AppTimer, event_dispatcher: AppCtxEventDispatcher<U>, window: Window) -> Self {
 
     #[cfg(not(feature = "futures"))] let _ = futures;
@@ -81,11 +99,22 @@ impl<U: EventLike> AppCtx<U> {
       #[cfg(feature = "frame_pacing")] manual_frame_duration: None,
       #[cfg(feature = "frame_pacing")] monitor_frame_duration: None,
       #[cfg(feature = "frame_pacing")] frame_duration: STD_FRAME_DURATION,
+      #[cfg(feature = "frame_pacing")] target_frame_duration: STD_FRAME_DURATION,
+      #[cfg(feature = "frame_pacing")] frame_stats: crate::timer::FrameStats::new(),
+      #[cfg(feature = "power_saving")] power: PowerSaving::new(),
+      #[cfg(feature = "animations")] animations: Animations::new(),
+      #[cfg(feature = "app_clock")] clock: ClockState::new(),
+      #[cfg(feature = "monitor_change")] monitor: MonitorTracker::new(),
       #[cfg(feature = "auto_wake_lock")] auto_wake_lock: false,
       exit: false,
 
       event_dispatcher,
 
+      #[cfg(feature = "event_bus")] event_bus: EventBus::new(),
+      #[cfg(feature = "screens")] screens: ScreenStack::new(),
+      #[cfg(feature = "input")] input: crate::input::InputState::new(),
+      #[cfg(feature = "regions")] regions: Regions::default(),
+
       window_id: window.id(),
       window: Arc::new(window),
     };
@@ -93,6 +122,11 @@ impl<U: EventLike> AppCtx<U> {
     #[cfg(feature = "frame_pacing")]
     app_ctx.fetch_monitor_frame_duration();
 
+    #[cfg(feature = "monitor_change")] {
+      app_ctx.check_monitor();
+      app_ctx.schedule_monitor_check();
+    }
+
     app_ctx
   }
 }
@@ -122,9 +156,50 @@ impl<U: EventLike> AppCtx<U> {
     self.timer.borrow_mut().set_timeout_wait_earlier(AppTimeoutId::User(id), duraion)
   }
 
+  pub fn set_timeout_leeway(&mut self, id: u64, instant: Instant, leeway: Duration) -> TimeoutResult {
+    self.timer.borrow_mut().set_timeout_leeway(AppTimeoutId::User(id), instant, leeway)
+  }
+
+  pub fn set_timeout_wait_leeway(&mut self, id: u64, duraion: Duration, leeway: Duration) -> TimeoutResult {
+    self.timer.borrow_mut().set_timeout_wait_leeway(AppTimeoutId::User(id), duraion, leeway)
+  }
+
   pub fn cancel_timeout(&mut self, id: u64, if_later: Option<Instant>) -> TimeoutResult {
     self.timer.borrow_mut().cancel_timeout(&AppTimeoutId::User(id), if_later)
   }
+
+  // wall-clock timeouts, see Timer::set_wall_timeout
+
+  pub fn get_wall_timeout(&self, id: u64) -> Option<SystemTime> {
+    self.timer.borrow().get_wall_timeout(&AppTimeoutId::User(id))
+  }
+
+  pub fn set_wall_timeout(&mut self, id: u64, time: SystemTime) -> TimeoutResult {
+    self.timer.borrow_mut().set_wall_timeout(AppTimeoutId::User(id), time)
+  }
+
+  pub fn set_wall_timeout_leeway(&mut self, id: u64, time: SystemTime, leeway: Duration) -> TimeoutResult {
+    self.timer.borrow_mut().set_wall_timeout_leeway(AppTimeoutId::User(id), time, leeway)
+  }
+}
+
+
+// timer precision, see Timer::set_precision
+
+#[cfg(any(feature = "timeout", feature = "async_timeout", feature = "frame_pacing"))]
+impl<U: EventLike> AppCtx<U> {
+
+  pub fn timer_precision(&self) -> Option<Duration> {
+    self.timer.borrow().precision()
+  }
+
+  pub fn set_timer_precision(&mut self, slack: Option<Duration>) {
+    self.timer.borrow_mut().set_precision(slack)
+  }
+
+  pub fn wake_stats(&self) -> WakeStats { *self.timer.borrow().wake_stats() }
+
+  pub fn reset_wake_stats(&mut self) { self.timer.borrow_mut().reset_wake_stats() }
 }
 
 
@@ -156,6 +231,10 @@ impl<U: EventLike> AppCtx<U> {
 
   pub fn frame_timeout(&self) -> Option<Instant> { self.frame_timeout }
 
+  pub fn frame_stats(&self) -> &crate::timer::FrameStats { &self.frame_stats }
+
+  pub fn reset_frame_stats(&mut self) { self.frame_stats.reset() }
+
   pub fn schedule_frame(&mut self, instant: Instant) {
 
     let set_instant = match self.frame_timeout {
@@ -177,15 +256,19 @@ impl<U: EventLike> AppCtx<U> {
   }
 
   pub fn request_frame(&mut self) {
-    self.schedule_frame(self.frame_time + self.frame_duration);
+    self.schedule_frame(self.frame_time + self.target_frame_duration);
   }
 
   pub fn sync_frame_time(&mut self, instant: Instant) {
-    self.frame_time = instant.min(Instant::now() + self.frame_duration);
+    self.frame_time = instant.min(Instant::now() + self.target_frame_duration);
   }
 
-  fn update_frame_duration(&mut self) {
+  pub(super) fn update_frame_duration(&mut self) {
     self.frame_duration = self.manual_frame_duration.or(self.monitor_frame_duration).unwrap_or(STD_FRAME_DURATION);
+    self.target_frame_duration = self.frame_duration;
+
+    #[cfg(feature = "power_saving")]
+    { self.target_frame_duration = self.power.target_frame_duration(self.frame_duration); }
   }
 
   pub fn set_frame_duration(&mut self, manual_duration: Option<Duration>) {
@@ -204,6 +287,9 @@ impl<U: EventLike> AppCtx<U> {
   pub fn monitor_frame_duration(&mut self) -> Option<Duration> { self.monitor_frame_duration }
 
   pub fn frame_duration(&mut self) -> Duration { self.frame_duration }
+
+  // the frame duration frames are paced at, may be longer while power saving
+  pub fn target_frame_duration(&self) -> Duration { self.target_frame_duration }
 }
 
 
@@ -0,0 +1,228 @@
+use std::{any::{Any, TypeId, type_name}, sync::Arc, rc::{Rc, Weak}, cell::RefCell, collections::VecDeque};
+use std::{pin::Pin, task::{Context, Poll, Waker}};
+use crate::rapidhash::RapidHashMap;
+use super::*;
+
+
+// message wrapper, travels through the event-loop
+
+#[derive(derive_more::Debug, Clone)]
+pub struct BusMessage {
+  type_id: TypeId,
+  type_name: &'static str,
+  #[debug(skip)] message: Arc<dyn Any + Send + Sync>,
+}
+
+impl BusMessage {
+
+  pub fn new<T: Any + Send + Sync>(message: T) -> Self {
+    Self { type_id: TypeId::of::<T>(), type_name: type_name::<T>(), message: Arc::new(message) }
+  }
+
+  pub fn type_id(&self) -> TypeId { self.type_id }
+  pub fn type_name(&self) -> &'static str { self.type_name }
+
+  pub fn downcast_ref<T: Any>(&self) -> Option<&T> { self.message.downcast_ref() }
+}
+
+
+// publisher which can be sent to other threads
+
+#[derive(Debug, Clone)]
+pub struct AppBusPublisher<U: EventLike> {
+  event_dispatcher: AppCtxEventDispatcher<U>,
+}
+
+impl<U: EventLike> AppBusPublisher<U> {
+  pub fn publish<T: Any + Send + Sync>(&self, message: T) {
+    self.event_dispatcher.dispatch(AppEventExt::BusMessage(BusMessage::new(message)));
+  }
+}
+
+
+// async receiver
+
+#[derive(Debug)]
+struct BusQueue<T> {
+  queue: VecDeque<Arc<T>>,
+  waker: Option<Waker>,
+}
+
+#[derive(Debug)]
+pub struct BusReceiver<T> {
+  inner: Rc<RefCell<BusQueue<T>>>,
+}
+
+impl<T> BusReceiver<T> {
+
+  pub fn len(&self) -> usize { self.inner.borrow().queue.len() }
+  pub fn is_empty(&self) -> bool { self.inner.borrow().queue.is_empty() }
+
+  pub fn try_recv(&self) -> Option<Arc<T>> {
+    let queue = &mut self.inner.borrow_mut().queue;
+    let message = queue.pop_front();
+    shrink_capacity!(queue, 16);
+    message
+  }
+
+  pub fn recv(&self) -> BusRecv<'_, T> { BusRecv { receiver: self } }
+}
+
+pub struct BusRecv<'a, T> { receiver: &'a BusReceiver<T> }
+
+impl<T> Future for BusRecv<'_, T> {
+  type Output = Arc<T>;
+
+  fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Arc<T>> {
+    if let Some(message) = self.receiver.try_recv() {
+      Poll::Ready(message)
+    } else {
+      self.receiver.inner.borrow_mut().waker = Some(context.waker().clone());
+      Poll::Pending
+    }
+  }
+}
+
+
+// type-erased receiver sink
+trait BusSink {
+  fn push(&self, message: &Arc<dyn Any + Send + Sync>) -> bool; // returns false if the receiver was dropped
+}
+
+impl<T: Any + Send + Sync> BusSink for Weak<RefCell<BusQueue<T>>> {
+  fn push(&self, message: &Arc<dyn Any + Send + Sync>) -> bool {
+
+    let Some(inner) = self.upgrade() else { return false };
+
+    if let Ok(message) = message.clone().downcast::<T>() {
+      let mut inner = inner.borrow_mut();
+      inner.queue.push_back(message);
+      if let Some(waker) = inner.waker.take() { waker.wake() }
+    }
+
+    true
+  }
+}
+
+
+// subscriptions
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BusSubscriptionId(u64);
+
+type BusHandler<U> = Box<dyn FnMut(&mut AppCtx<U>, &dyn Any)>;
+
+enum BusSubscriber<U: EventLike> {
+  Handler(BusHandler<U>),
+  Receiver(Box<dyn BusSink>),
+}
+
+
+pub(super) struct EventBus<U: EventLike> {
+  subscribers: RapidHashMap<TypeId, Vec<(BusSubscriptionId, BusSubscriber<U>)>>,
+  unsubscribed: Vec<BusSubscriptionId>, // unsubscribed while delivering
+  delivering: Option<TypeId>,
+  next_id: u64,
+}
+
+impl<U: EventLike> EventBus<U> {
+
+  pub fn new() -> Self {
+    Self { subscribers: Default::default(), unsubscribed: Vec::new(), delivering: None, next_id: 0 }
+  }
+
+  fn subscribe(&mut self, type_id: TypeId, subscriber: BusSubscriber<U>) -> BusSubscriptionId {
+    let id = BusSubscriptionId(self.next_id);
+    self.next_id = self.next_id.wrapping_add(1);
+    self.subscribers.entry(type_id).or_default().push((id, subscriber));
+    id
+  }
+
+  fn unsubscribe(&mut self, id: BusSubscriptionId) {
+
+    for (type_id, subscribers) in self.subscribers.iter_mut() {
+      if let Some(i) = subscribers.iter().position(|(other, _)| *other == id) {
+        subscribers.remove(i);
+        if subscribers.is_empty() {
+          let type_id = *type_id;
+          self.subscribers.remove(&type_id);
+        }
+        return;
+      }
+    }
+
+    if self.delivering.is_some() {
+      self.unsubscribed.push(id);
+    }
+  }
+
+  pub fn deliver(app_ctx: &mut AppCtx<U>, message: BusMessage) {
+
+    // take subscribers out, so that handlers may access the app_ctx freely
+    let Some(mut subscribers) = app_ctx.event_bus.subscribers.remove(&message.type_id) else { return };
+
+    let previous = app_ctx.event_bus.delivering.replace(message.type_id);
+
+    subscribers.retain_mut(|(id, subscriber)| {
+
+      if app_ctx.event_bus.unsubscribed.contains(id) {
+        return false;
+      }
+
+      match subscriber {
+        BusSubscriber::Handler(handler) => { handler(app_ctx, &*message.message); true },
+        BusSubscriber::Receiver(sink) => sink.push(&message.message),
+      }
+    });
+
+    let bus = &mut app_ctx.event_bus;
+
+    bus.delivering = previous;
+
+    if !bus.unsubscribed.is_empty() {
+      subscribers.retain(|(id, _)| !bus.unsubscribed.contains(id));
+      if bus.delivering.is_none() { bus.unsubscribed.clear() }
+    }
+
+    // merge with subscriptions made during delivery
+    if let Some(added) = bus.subscribers.remove(&message.type_id) {
+      subscribers.extend(added);
+    }
+
+    if !subscribers.is_empty() {
+      bus.subscribers.insert(message.type_id, subscribers);
+    }
+
+    shrink_capacity!(bus.subscribers, 64);
+  }
+}
+
+
+// app-ctx interface
+
+impl<U: EventLike> AppCtx<U> {
+
+  pub fn publish<T: Any + Send + Sync>(&self, message: T) {
+    self.event_dispatcher.dispatch(AppEventExt::BusMessage(BusMessage::new(message)));
+  }
+
+  pub fn bus_publisher(&self) -> AppBusPublisher<U> {
+    AppBusPublisher { event_dispatcher: self.event_dispatcher.clone() }
+  }
+
+  pub fn subscribe<T: Any + Send + Sync>(&mut self, mut handler: impl FnMut(&mut AppCtx<U>, &T) + 'static) -> BusSubscriptionId {
+    self.event_bus.subscribe(TypeId::of::<T>(), BusSubscriber::Handler(Box::new(move |app_ctx, message| {
+      if let Some(message) = message.downcast_ref::<T>() { handler(app_ctx, message) }
+    })))
+  }
+
+  pub fn subscribe_receiver<T: Any + Send + Sync>(&mut self) -> BusReceiver<T> {
+    let inner = Rc::new(RefCell::new(BusQueue { queue: VecDeque::new(), waker: None }));
+    self.event_bus.subscribe(TypeId::of::<T>(), BusSubscriber::Receiver(Box::new(Rc::downgrade(&inner))));
+    BusReceiver { inner }
+  }
+
+  pub fn unsubscribe(&mut self, id: BusSubscriptionId) {
+    self.event_bus.unsubscribe(id);
+  }
+}
@@ -0,0 +1,57 @@
+use super::*;
+
+
+// middleware layers, wrapping the app handler
+
+pub type AppLayerNext<'a, U> = &'a mut dyn FnMut(&mut AppCtx<U>, Event<U>);
+
+pub trait AppLayer<U: EventLike = !>: 'static {
+
+  // called before AppHandler::init
+  fn init(&mut self, _app_ctx: &mut AppCtx<U>) {}
+
+  // call next to pass the (possibly transformed) event on to the inner layers and the handler
+  fn event(&mut self, app_ctx: &mut AppCtx<U>, event: Event<U>, next: AppLayerNext<'_, U>) {
+    next(app_ctx, event)
+  }
+}
+
+pub type BoxAppLayer<U> = Box<dyn AppLayer<U>>;
+
+
+// outermost layer first
+pub(super) fn dispatch_event<App: AppHandler>(
+  layers: &mut [BoxAppLayer<App::UserEvent>], app: &mut App,
+  app_ctx: &mut AppCtx<App::UserEvent>, event: Event<App::UserEvent>,
+) {
+  match layers.split_first_mut() {
+    Some((layer, inner)) => layer.event(app_ctx, event, &mut |app_ctx, event| dispatch_event(inner, app, app_ctx, event)),
+    None => {
+      #[cfg(feature = "screens")]
+      if ScreenStack::route(app_ctx, &event) { return }
+      app.event(app_ctx, event)
+    },
+  }
+}
+
+
+// common layers
+
+#[derive(Debug, Clone, Copy)]
+pub struct EventLogLayer {
+  pub level: LogLevel,
+  pub redraw: bool, // log RedrawRequested as well
+}
+
+impl EventLogLayer {
+  pub fn new(level: LogLevel) -> Self { Self { level, redraw: false } }
+}
+
+impl<U: EventLike> AppLayer<U> for EventLogLayer {
+  fn event(&mut self, app_ctx: &mut AppCtx<U>, event: Event<U>, next: AppLayerNext<'_, U>) {
+    if self.redraw || !matches!(event, Event::WindowEvent(WindowEvent::RedrawRequested)) {
+      log::log!(self.level, "{event:?}");
+    }
+    next(app_ctx, event)
+  }
+}
@@ -8,11 +8,13 @@ mod ctx;
 mod state;
 mod mount;
 mod futures;
+mod layer;
 
 pub use ctx::*;
 use state::*;
 pub use mount::*;
 pub use futures::*;
+pub use layer::*;
 
 
 #[cfg(all(feature = "web_clipboard", target_family="wasm", web_sys_unstable_apis))]
@@ -22,6 +24,55 @@ mod web_clipboard;
 pub use web_clipboard::*;
 
 
+#[cfg(feature = "screens")]
+mod screens;
+
+#[cfg(feature = "screens")]
+pub use screens::*;
+
+
+#[cfg(feature = "event_bus")]
+mod event_bus;
+
+#[cfg(feature = "event_bus")]
+pub use event_bus::*;
+
+
+#[cfg(feature = "power_saving")]
+mod power;
+
+#[cfg(feature = "power_saving")]
+pub use power::*;
+
+
+#[cfg(feature = "animations")]
+mod animations;
+
+#[cfg(feature = "animations")]
+pub use animations::*;
+
+
+#[cfg(feature = "monitor_change")]
+mod monitor;
+
+#[cfg(feature = "monitor_change")]
+pub use monitor::*;
+
+
+#[cfg(feature = "app_clock")]
+mod clock;
+
+#[cfg(feature = "app_clock")]
+pub use clock::*;
+
+
+#[cfg(feature = "regions")]
+mod regions;
+
+#[cfg(feature = "regions")]
+pub use regions::*;
+
+
 // types
 
 #[cfg(feature = "frame_pacing")]
@@ -31,6 +82,7 @@ pub const STD_FRAME_DURATION: time::Duration = time::Duration::from_nanos(10u64.
 #[derive(Debug, Clone)]
 pub enum AppEventExt<U: EventLike = !> {
   UserEvent(U),
+  #[cfg(feature = "event_bus")] BusMessage(BusMessage),
   #[cfg(all(feature = "web_clipboard", target_family="wasm"))] ClipboardFetch(WindowId),
   #[cfg(all(feature = "web_clipboard", target_family="wasm"))] ClipboardPaste(WindowId),
 }
@@ -38,7 +90,9 @@ pub enum AppEventExt<U: EventLike = !> {
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum AppTimeoutId {
   #[cfg(feature = "timeout")] User(u64),
+  #[cfg(all(feature = "app_clock", feature = "timeout"))] AppClock(u64),
   #[cfg(feature = "frame_pacing")] FrameRequest(WindowId),
+  #[cfg(feature = "monitor_change")] MonitorCheck(WindowId),
   #[cfg(feature = "async_timeout")] Async(AsyncTimeoutId),
 }
 
@@ -58,10 +112,14 @@ pub enum Event<U: EventLike = !> {
   WindowEvent(WindowEvent),
   UserEvent(U),
   #[cfg(feature = "timeout")] Timeout {instant: time::Instant, id: u64},
+  #[cfg(feature = "timeout")] ClockChanged(ClockChange),
+  #[cfg(all(feature = "app_clock", feature = "timeout"))] AppTimeout {elapsed: time::Duration, id: u64},
   #[cfg(feature = "futures")] FutureReady(AppFutureId),
   #[cfg(all(feature = "web_clipboard", target_family="wasm"))] ClipboardFetch,
   #[cfg(all(feature = "web_clipboard", target_family="wasm"))] ClipboardPaste,
   #[cfg(feature = "device_events")] DeviceEvent {device_id: DeviceId, event: DeviceEvent},
+  #[cfg(feature = "regions")] Region(RegionEvent),
+  #[cfg(feature = "monitor_change")] MonitorChanged(MonitorInfo),
 }
 
 
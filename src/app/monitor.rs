@@ -0,0 +1,153 @@
+use winit::{window::Window, monitor::MonitorHandle, dpi::{PhysicalSize, PhysicalPosition}};
+use crate::time::{Instant, Duration};
+use super::*;
+
+
+// monitor-change feature, detects moving to another monitor or monitor changes
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorInfo {
+  pub name: Option<String>,
+  pub refresh_rate_millihertz: Option<u32>,
+  pub scale_factor: f64,
+  pub size: PhysicalSize<u32>,
+  pub position: PhysicalPosition<i32>,
+}
+
+impl MonitorInfo {
+
+  pub fn new(monitor: &MonitorHandle) -> Self {
+    Self {
+      name: monitor.name(),
+      refresh_rate_millihertz: monitor.refresh_rate_millihertz(),
+      scale_factor: monitor.scale_factor(),
+      size: monitor.size(),
+      position: monitor.position(),
+    }
+  }
+
+  pub fn frame_duration(&self) -> Option<Duration> {
+    self.refresh_rate_millihertz.filter(|mhz| *mhz > 0).map(|mhz| Duration::from_nanos(10_u64.pow(12) / mhz as u64))
+  }
+}
+
+
+// catches monitor hotplug and changes not signaled by window events
+const MONITOR_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+
+#[derive(Debug)]
+pub(super) struct MonitorTracker<M: PartialEq = MonitorHandle> {
+  monitor: Option<M>,
+  info: Option<MonitorInfo>,
+  check_interval: Option<Duration>, // None disables periodic checks
+  occluded: bool, // no periodic checks while occluded
+}
+
+impl MonitorTracker {
+
+  fn check(&mut self, window: &Window) -> Option<Option<MonitorInfo>> {
+    let monitor = window.current_monitor();
+    let info = monitor.as_ref().map(MonitorInfo::new);
+    self.update(monitor, info)
+  }
+}
+
+impl<M: PartialEq> MonitorTracker<M> {
+
+  pub(super) fn new() -> Self {
+    Self { monitor: None, info: None, check_interval: Some(MONITOR_CHECK_INTERVAL), occluded: false }
+  }
+
+  // returns the new monitor info when the monitor or its properties changed
+  fn update(&mut self, monitor: Option<M>, info: Option<MonitorInfo>) -> Option<Option<MonitorInfo>> {
+    if monitor != self.monitor || info != self.info {
+      self.monitor = monitor;
+      self.info = info.clone();
+      Some(info)
+    }
+    else { None }
+  }
+}
+
+
+impl<U: EventLike> AppCtx<U> {
+
+  // None if the current monitor is unknown
+  pub fn monitor(&self) -> Option<&MonitorInfo> { self.monitor.info.as_ref() }
+
+  pub fn monitor_check_interval(&self) -> Option<Duration> { self.monitor.check_interval }
+
+  // periodic checks catch changes not signaled by window events, e.g. monitor hotplug
+  // None disables them, so that the event loop is not woken up regularly
+  pub fn set_monitor_check_interval(&mut self, interval: Option<Duration>) {
+    self.monitor.check_interval = interval;
+    self.schedule_monitor_check();
+  }
+
+  // updates the frame duration on changes, returns the monitor info to dispatch
+  pub(super) fn check_monitor(&mut self) -> Option<MonitorInfo> {
+    let info = self.monitor.check(&self.window)?;
+    self.fetch_monitor_frame_duration();
+    info
+  }
+
+  // pauses periodic checks while occluded, checks when visible again
+  pub(super) fn monitor_occluded(&mut self, occluded: bool) -> Option<MonitorInfo> {
+    self.monitor.occluded = occluded;
+    self.schedule_monitor_check();
+    if occluded { None } else { self.check_monitor() }
+  }
+
+  pub(super) fn schedule_monitor_check(&mut self) {
+    let id = AppTimeoutId::MonitorCheck(self.window.id());
+    let mut timer = self.timer.borrow_mut();
+
+    let interval = self.monitor.check_interval.filter(|_| !self.monitor.occluded);
+
+    let _ = match interval.and_then(|interval| Instant::now().checked_add(interval)) {
+      Some(instant) => timer.set_timeout(id, instant),
+      None => timer.cancel_timeout(&id, None),
+    };
+  }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  fn info(refresh_rate_millihertz: Option<u32>) -> MonitorInfo {
+    MonitorInfo {
+      name: None, refresh_rate_millihertz, scale_factor: 1.0,
+      size: PhysicalSize::new(1920, 1080), position: PhysicalPosition::new(0, 0),
+    }
+  }
+
+  #[test]
+  fn frame_duration() {
+    assert_eq!(info(Some(60_000)).frame_duration(), Some(Duration::from_nanos(16_666_666)));
+    assert_eq!(info(Some(0)).frame_duration(), None);
+    assert_eq!(info(None).frame_duration(), None);
+  }
+
+  #[test]
+  fn monitor_changes() {
+
+    let mut tracker = MonitorTracker::<u32>::new();
+    assert_eq!(tracker.check_interval, Some(MONITOR_CHECK_INTERVAL));
+
+    assert_eq!(tracker.update(Some(1), Some(info(Some(60_000)))), Some(Some(info(Some(60_000)))));
+    assert_eq!(tracker.update(Some(1), Some(info(Some(60_000)))), None);
+
+    // same monitor with a new mode
+    assert_eq!(tracker.update(Some(1), Some(info(Some(120_000)))), Some(Some(info(Some(120_000)))));
+
+    // other monitor with the same properties
+    assert_eq!(tracker.update(Some(2), Some(info(Some(120_000)))), Some(Some(info(Some(120_000)))));
+
+    assert_eq!(tracker.update(None, None), Some(None));
+  }
+}
@@ -1,6 +1,6 @@
 
 use winit::{window::{WindowAttributes, Window}, event::*, event_loop::ActiveEventLoop};
-use std::{mem::replace, sync::mpsc::{Receiver, sync_channel}};
+use std::{mem::{replace, take}, sync::mpsc::{Receiver, sync_channel}};
 
 use crate::*;
 use super::*;
@@ -29,12 +29,22 @@ enum MountState<App: AppHandler> {
 
 pub struct AppMount<App: AppHandler> {
   state: MountState<App>,
+  layers: Vec<BoxAppLayer<App::UserEvent>>,
 }
 
 
 impl<App: AppHandler> AppMount<App> {
   pub fn new(window_attributes: WindowAttributes, init_data: App::InitData) -> Self {
-    Self { state: MountState::Init { event_queue: Vec::new(), window_attributes, init_data } }
+    Self {
+      state: MountState::Init { event_queue: Vec::new(), window_attributes, init_data },
+      layers: Vec::new(),
+    }
+  }
+
+  // layers added first wrap layers added later
+  pub fn with_layer(mut self, layer: impl AppLayer<App::UserEvent>) -> Self {
+    self.layers.push(Box::new(layer));
+    self
   }
 
   fn take(&mut self) -> MountState<App> {
@@ -97,11 +107,13 @@ impl<App: AppHandler> Runtime for AppMount<App> {
           let futures = ctx.futures.clone();
           let timer = ctx.timer.clone();
           let event_dispatcher = ctx.event_dispatcher.clone();
+          let mut layers = take(&mut self.layers);
 
           let future_id = ctx.futures.spawn(Box::pin(async move {
             let mut app_ctx = AppCtx::new(futures, timer, event_dispatcher, window);
+            for layer in layers.iter_mut() { layer.init(&mut app_ctx) }
             let app = App::init(&mut app_ctx, init_data).await;
-            let app_state = AppState::new(app_ctx, app);
+            let app_state = AppState::new(app_ctx, layers, app);
             sender.send(app_state).unwrap();
           }));
 
@@ -0,0 +1,197 @@
+use crate::time::{Instant, Duration};
+use super::*;
+
+
+// power-saving feature, throttles frame pacing in the background
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PowerState {
+  Active,
+  Idle, // no input for PowerPolicy::idle_after
+  Unfocused,
+  Occluded,
+}
+
+// minimal frame durations per state, None is the full rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PowerPolicy {
+  pub unfocused: Option<Duration>,
+  pub occluded: Option<Duration>,
+  pub idle: Option<Duration>,
+  pub idle_after: Duration,
+}
+
+impl Default for PowerPolicy {
+  fn default() -> Self {
+    Self {
+      unfocused: Some(Duration::from_nanos(10u64.pow(9)/20)),
+      occluded: Some(Duration::from_secs(1)),
+      idle: Some(Duration::from_nanos(10u64.pow(9)/30)),
+      idle_after: Duration::from_secs(30),
+    }
+  }
+}
+
+impl PowerPolicy {
+
+  pub const FULL_RATE: Self = Self { unfocused: None, occluded: None, idle: None, idle_after: Duration::MAX };
+
+  pub fn frame_duration(&self, state: PowerState) -> Option<Duration> {
+    match state {
+      PowerState::Active => None,
+      PowerState::Idle => self.idle,
+      PowerState::Unfocused => self.unfocused,
+      PowerState::Occluded => self.occluded,
+    }
+  }
+}
+
+
+#[derive(Debug)]
+pub(super) struct PowerSaving {
+  pub(super) policy: PowerPolicy,
+  pub(super) state: PowerState,
+  focused: bool,
+  occluded: bool,
+  last_input: Instant,
+}
+
+impl PowerSaving {
+
+  pub(super) fn new() -> Self {
+    Self { policy: PowerPolicy::default(), state: PowerState::Active, focused: true, occluded: false, last_input: Instant::now() }
+  }
+
+  fn is_input(event: &WindowEvent) -> bool {
+    matches!(event,
+      WindowEvent::KeyboardInput {..} | WindowEvent::MouseInput {..} | WindowEvent::CursorMoved {..} |
+      WindowEvent::MouseWheel {..} | WindowEvent::Touch(_) | WindowEvent::PinchGesture {..} |
+      WindowEvent::PanGesture {..} | WindowEvent::RotationGesture {..} | WindowEvent::DoubleTapGesture {..} |
+      WindowEvent::Ime(_) | WindowEvent::DroppedFile(_)
+    )
+  }
+
+  // returns if the state changed
+  pub(super) fn window_event(&mut self, event: &WindowEvent, instant: Instant) -> bool {
+    match event {
+      WindowEvent::Focused(focused) => self.focused = *focused,
+      WindowEvent::Occluded(occluded) => self.occluded = *occluded,
+      event if Self::is_input(event) => self.last_input = instant,
+      _ => {},
+    }
+    self.update(instant)
+  }
+
+  // the frame duration to pace frames at, never faster than the monitor
+  pub(super) fn target_frame_duration(&self, frame_duration: Duration) -> Duration {
+    self.policy.frame_duration(self.state).map_or(frame_duration, |throttled| frame_duration.max(throttled))
+  }
+
+  pub(super) fn update(&mut self, instant: Instant) -> bool {
+
+    let state =
+      if self.occluded { PowerState::Occluded }
+      else if !self.focused { PowerState::Unfocused }
+      else if instant.saturating_duration_since(self.last_input) >= self.policy.idle_after { PowerState::Idle }
+      else { PowerState::Active };
+
+    let changed = state != self.state;
+    self.state = state;
+    changed
+  }
+}
+
+
+impl<U: EventLike> AppCtx<U> {
+
+  pub fn power_state(&self) -> PowerState { self.power.state }
+
+  pub fn power_policy(&self) -> &PowerPolicy { &self.power.policy }
+
+  pub fn set_power_policy(&mut self, policy: PowerPolicy) {
+    self.power.policy = policy;
+    self.power.update(Instant::now());
+    self.power_state_changed();
+  }
+
+  // counts as input for the idle detection, e.g. on device events or network activity
+  pub fn mark_active(&mut self) {
+    self.power.last_input = Instant::now();
+    if self.power.update(self.power.last_input) { self.power_state_changed() }
+  }
+
+  pub(super) fn power_window_event(&mut self, event: &WindowEvent) {
+    if self.power.window_event(event, Instant::now()) { self.power_state_changed() }
+  }
+
+  fn power_state_changed(&mut self) {
+    self.update_frame_duration();
+    // pull a throttled, pending frame forward when returning to a faster rate
+    if self.frame_timeout.is_some() { self.request_frame() }
+  }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+  use winit::dpi::PhysicalPosition;
+
+  #[test]
+  fn power_states() {
+
+    let mut power = PowerSaving::new();
+    let start = power.last_input;
+    let at = |secs| start + Duration::from_secs(secs);
+
+    let input = WindowEvent::CursorMoved { device_id: DeviceId::dummy(), position: PhysicalPosition::new(0.0, 0.0) };
+
+    assert!(!power.update(at(29)));
+    assert!(power.update(at(30)));
+    assert_eq!(power.state, PowerState::Idle);
+    assert_eq!(power.policy.frame_duration(power.state), PowerPolicy::default().idle);
+
+    // input restores the full rate
+    assert!(power.window_event(&input, at(31)));
+    assert_eq!(power.state, PowerState::Active);
+    assert_eq!(power.policy.frame_duration(power.state), None);
+
+    power.window_event(&WindowEvent::Focused(false), at(32));
+    assert_eq!(power.state, PowerState::Unfocused);
+
+    // occlusion takes precedence
+    power.window_event(&WindowEvent::Occluded(true), at(33));
+    assert_eq!(power.state, PowerState::Occluded);
+    power.window_event(&WindowEvent::Occluded(false), at(34));
+    power.window_event(&WindowEvent::Focused(true), at(34));
+    assert_eq!(power.state, PowerState::Active);
+
+    power.policy = PowerPolicy::FULL_RATE;
+    assert!(!power.update(at(3600)));
+  }
+
+  #[test]
+  fn throttled_frames_not_missed() {
+
+    let mut power = PowerSaving::new();
+    let frame = Duration::from_millis(10);
+
+    power.window_event(&WindowEvent::Occluded(true), power.last_input);
+    let target = power.target_frame_duration(frame);
+    assert_eq!(target, Duration::from_secs(1));
+
+    // frames paced at the throttled rate
+    let mut stats = crate::timer::FrameStats::new();
+    let mut instant = power.last_input;
+    for _ in 0..5 {
+      stats.add_frame(instant, target, true);
+      instant += target;
+    }
+    assert_eq!(stats.missed, 0);
+
+    power.window_event(&WindowEvent::Occluded(false), instant);
+    assert_eq!(power.target_frame_duration(frame), frame);
+  }
+}
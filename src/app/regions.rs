@@ -0,0 +1,327 @@
+use std::rc::Rc;
+use winit::dpi::PhysicalPosition;
+use super::*;
+
+
+// hit-test regions, registered each frame in physical pixels
+
+pub type RegionId = u64;
+
+#[derive(derive_more::Debug, Clone)]
+pub enum RegionShape {
+  Rect { x: f64, y: f64, width: f64, height: f64 },
+  Circle { x: f64, y: f64, radius: f64 },
+  Custom(#[debug(skip)] Rc<dyn Fn(PhysicalPosition<f64>) -> bool>),
+}
+
+impl RegionShape {
+  pub fn contains(&self, pos: PhysicalPosition<f64>) -> bool {
+    match self {
+      Self::Rect { x, y, width, height } => pos.x >= *x && pos.x < x + width && pos.y >= *y && pos.y < y + height,
+      Self::Circle { x, y, radius } => (pos.x - x).powi(2) + (pos.y - y).powi(2) <= radius * radius,
+      Self::Custom(contains) => contains(pos),
+    }
+  }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionPointer {
+  Mouse,
+  Touch(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegionEventKind {
+  Enter,
+  Leave,
+  Down(MouseButton), // touches act as the left button
+  Up(MouseButton),
+  Move,
+}
+
+// dispatched as Event::Region right before the window event it was derived from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegionEvent {
+  pub region: RegionId,
+  pub pointer: RegionPointer,
+  pub kind: RegionEventKind,
+  pub position: PhysicalPosition<f64>,
+  pub captured: bool, // the pointer is held and captured by the region, it may be outside of it
+}
+
+
+#[derive(Debug)]
+struct Region {
+  id: RegionId,
+  z: i32,
+  shape: RegionShape,
+}
+
+#[derive(Debug)]
+struct PointerState {
+  pointer: RegionPointer,
+  position: PhysicalPosition<f64>,
+  hovered: Option<RegionId>,
+  buttons: Vec<MouseButton>,
+  captured: Option<RegionId>,
+}
+
+
+#[derive(Debug, Default)]
+pub struct Regions {
+  regions: Vec<Region>,
+  pointers: Vec<PointerState>,
+}
+
+impl Regions {
+
+  // topmost region at position, later registrations are on top within the same z
+  pub fn hit_test(&self, pos: PhysicalPosition<f64>) -> Option<RegionId> {
+    // max_by_key returns the last maximum
+    self.regions.iter()
+      .filter(|region| region.shape.contains(pos))
+      .max_by_key(|region| region.z)
+      .map(|region| region.id)
+  }
+
+  pub fn contains(&self, id: RegionId) -> bool { self.regions.iter().any(|region| region.id == id) }
+
+  pub fn hovered(&self, pointer: RegionPointer) -> Option<RegionId> {
+    self.pointer(pointer).and_then(|state| state.hovered)
+  }
+
+  pub fn captured(&self, pointer: RegionPointer) -> Option<RegionId> {
+    self.pointer(pointer).and_then(|state| state.captured)
+  }
+
+  // the region receiving the pointer, the captured one or else the hovered one
+  pub fn target(&self, pointer: RegionPointer) -> Option<RegionId> {
+    self.pointer(pointer).and_then(|state| state.captured.or(state.hovered))
+  }
+
+  fn pointer(&self, pointer: RegionPointer) -> Option<&PointerState> {
+    self.pointers.iter().find(|state| state.pointer == pointer)
+  }
+
+  pub(super) fn clear(&mut self) { self.regions.clear() }
+
+  pub(super) fn add(&mut self, id: RegionId, z: i32, shape: RegionShape) {
+    self.regions.push(Region { id, z, shape });
+  }
+
+  pub(super) fn update(&mut self, event: &WindowEvent) -> Vec<RegionEvent> {
+
+    let mut events = Vec::new();
+
+    match event {
+
+      WindowEvent::CursorMoved { position, .. } => {
+        self.move_pointer(RegionPointer::Mouse, *position, &mut events);
+      },
+
+      WindowEvent::CursorLeft { .. } => {
+        if let Some(i) = self.index(RegionPointer::Mouse) && self.pointers[i].captured.is_none() {
+          self.remove_pointer(i, &mut events);
+        }
+      },
+
+      WindowEvent::MouseInput { state, button, .. } => {
+        if let Some(i) = self.index(RegionPointer::Mouse) {
+          match state {
+            ElementState::Pressed => self.press(i, *button, &mut events),
+            ElementState::Released => self.release(i, *button, true, &mut events),
+          }
+        }
+      },
+
+      WindowEvent::Touch(touch) => {
+        let pointer = RegionPointer::Touch(touch.id);
+        match touch.phase {
+          TouchPhase::Started => {
+            self.move_pointer(pointer, touch.location, &mut events);
+            if let Some(i) = self.index(pointer) { self.press(i, MouseButton::Left, &mut events) }
+          },
+          TouchPhase::Moved => self.move_pointer(pointer, touch.location, &mut events),
+          TouchPhase::Ended | TouchPhase::Cancelled => {
+            if let Some(i) = self.index(pointer) {
+              self.pointers[i].position = touch.location;
+              self.release(i, MouseButton::Left, false, &mut events);
+              self.remove_pointer(i, &mut events);
+            }
+          },
+        }
+      },
+
+      // release events will be missed, release the held buttons now
+      WindowEvent::Focused(false) => {
+        for i in 0..self.pointers.len() {
+          for button in self.pointers[i].buttons.clone() {
+            self.release(i, button, true, &mut events);
+          }
+        }
+      },
+
+      _ => {},
+    }
+
+    events
+  }
+
+
+  fn index(&self, pointer: RegionPointer) -> Option<usize> {
+    self.pointers.iter().position(|state| state.pointer == pointer)
+  }
+
+  fn event(state: &PointerState, region: RegionId, kind: RegionEventKind) -> RegionEvent {
+    RegionEvent {
+      region, kind, pointer: state.pointer, position: state.position,
+      captured: state.captured == Some(region),
+    }
+  }
+
+  fn move_pointer(&mut self, pointer: RegionPointer, position: PhysicalPosition<f64>, events: &mut Vec<RegionEvent>) {
+
+    let i = self.index(pointer).unwrap_or_else(|| {
+      self.pointers.push(PointerState { pointer, position, hovered: None, buttons: Vec::new(), captured: None });
+      self.pointers.len() - 1
+    });
+
+    self.pointers[i].position = position;
+
+    // hover does not change while captured
+    if self.pointers[i].captured.is_none() {
+      self.update_hover(i, events);
+    }
+
+    let state = &self.pointers[i];
+    if let Some(target) = state.captured.or(state.hovered) {
+      events.push(Self::event(state, target, RegionEventKind::Move));
+    }
+  }
+
+  fn update_hover(&mut self, i: usize, events: &mut Vec<RegionEvent>) {
+
+    let hit = self.hit_test(self.pointers[i].position);
+    let state = &mut self.pointers[i];
+
+    if hit != state.hovered {
+      if let Some(previous) = state.hovered { events.push(Self::event(state, previous, RegionEventKind::Leave)) }
+      state.hovered = hit;
+      if let Some(hit) = hit { events.push(Self::event(state, hit, RegionEventKind::Enter)) }
+    }
+  }
+
+  fn press(&mut self, i: usize, button: MouseButton, events: &mut Vec<RegionEvent>) {
+
+    let state = &mut self.pointers[i];
+
+    if !state.buttons.contains(&button) { state.buttons.push(button) }
+    if state.captured.is_none() { state.captured = state.hovered }
+
+    if let Some(target) = state.captured {
+      events.push(Self::event(state, target, RegionEventKind::Down(button)));
+    }
+  }
+
+  fn release(&mut self, i: usize, button: MouseButton, rehover: bool, events: &mut Vec<RegionEvent>) {
+
+    let state = &mut self.pointers[i];
+
+    state.buttons.retain(|other| *other != button);
+
+    if let Some(target) = state.captured.or(state.hovered) {
+      events.push(Self::event(state, target, RegionEventKind::Up(button)));
+    }
+
+    if state.buttons.is_empty() && state.captured.take().is_some() && rehover {
+      self.update_hover(i, events);
+    }
+  }
+
+  fn remove_pointer(&mut self, i: usize, events: &mut Vec<RegionEvent>) {
+    let state = self.pointers.remove(i);
+    if let Some(hovered) = state.hovered {
+      events.push(Self::event(&state, hovered, RegionEventKind::Leave));
+    }
+  }
+}
+
+
+impl<U: EventLike> AppCtx<U> {
+
+  pub fn regions(&self) -> &Regions { &self.regions }
+
+  // registered regions are cleared before each RedrawRequested-event, register them while drawing
+  pub fn add_region(&mut self, id: RegionId, z: i32, shape: RegionShape) {
+    self.regions.add(id, z, shape);
+  }
+
+  pub fn clear_regions(&mut self) { self.regions.clear() }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  const DEVICE_ID: DeviceId = DeviceId::dummy();
+
+  fn rect(x: f64, width: f64) -> RegionShape { RegionShape::Rect { x, y: 0.0, width, height: 10.0 } }
+
+  fn moved(x: f64) -> WindowEvent {
+    WindowEvent::CursorMoved { device_id: DEVICE_ID, position: PhysicalPosition::new(x, 5.0) }
+  }
+
+  fn button(state: ElementState) -> WindowEvent {
+    WindowEvent::MouseInput { device_id: DEVICE_ID, state, button: MouseButton::Left }
+  }
+
+  fn kinds(events: Vec<RegionEvent>) -> Vec<(RegionId, RegionEventKind)> {
+    events.into_iter().map(|event| (event.region, event.kind)).collect()
+  }
+
+  #[test]
+  fn hit_test_order() {
+
+    let mut regions = Regions::default();
+    regions.add(1, 0, rect(0.0, 10.0));
+    regions.add(2, 0, rect(0.0, 10.0));
+    regions.add(3, -1, rect(0.0, 20.0));
+
+    let pos = |x| PhysicalPosition::new(x, 5.0);
+
+    assert_eq!(regions.hit_test(pos(5.0)), Some(2)); // later registration on the same z
+    assert_eq!(regions.hit_test(pos(15.0)), Some(3));
+    assert_eq!(regions.hit_test(pos(25.0)), None);
+  }
+
+  #[test]
+  fn hover_and_capture() {
+
+    use RegionEventKind::*;
+
+    let mut regions = Regions::default();
+    regions.add(1, 0, rect(0.0, 10.0));
+    regions.add(2, 0, rect(10.0, 10.0));
+
+    assert_eq!(kinds(regions.update(&moved(5.0))), [(1, Enter), (1, Move)]);
+    assert_eq!(kinds(regions.update(&moved(15.0))), [(1, Leave), (2, Enter), (2, Move)]);
+
+    // captured while held, hover changes on release
+    assert_eq!(kinds(regions.update(&button(ElementState::Pressed))), [(2, Down(MouseButton::Left))]);
+    assert_eq!(kinds(regions.update(&moved(5.0))), [(2, Move)]);
+    assert_eq!(regions.captured(RegionPointer::Mouse), Some(2));
+
+    assert_eq!(kinds(regions.update(&button(ElementState::Released))), [(2, Up(MouseButton::Left)), (2, Leave), (1, Enter)]);
+    assert_eq!(regions.target(RegionPointer::Mouse), Some(1));
+
+    // focus loss releases the capture
+    regions.update(&button(ElementState::Pressed));
+    regions.update(&moved(15.0));
+    assert_eq!(kinds(regions.update(&WindowEvent::Focused(false))), [(1, Up(MouseButton::Left)), (1, Leave), (2, Enter)]);
+    assert_eq!(regions.captured(RegionPointer::Mouse), None);
+  }
+}
@@ -0,0 +1,265 @@
+use std::mem::take;
+use super::*;
+
+
+// screen/state stack
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScreenFlow {
+  Consume, // neither lower screens nor the app handler receive the event
+  PassThrough, // pass the event to the screen below as well
+}
+
+pub trait Screen<U: EventLike = !>: 'static {
+
+  fn enter(&mut self, _app_ctx: &mut AppCtx<U>) {}
+
+  // spawned on the AppFutureSpawner after enter, calls entered when ready
+  #[cfg(feature = "futures")]
+  fn enter_async(&mut self, _app_ctx: &mut AppCtx<U>) -> Option<AppFuture> { None }
+
+  #[cfg(feature = "futures")]
+  fn entered(&mut self, _app_ctx: &mut AppCtx<U>) {}
+
+  fn exit(&mut self, _app_ctx: &mut AppCtx<U>) {}
+
+  // another screen was pushed on top / the screen on top was popped
+  fn pause(&mut self, _app_ctx: &mut AppCtx<U>) {}
+  fn resume(&mut self, _app_ctx: &mut AppCtx<U>) {}
+
+  fn event(&mut self, app_ctx: &mut AppCtx<U>, event: &Event<U>) -> ScreenFlow;
+}
+
+pub type BoxScreen<U> = Box<dyn Screen<U>>;
+
+
+enum ScreenTransition<S> {
+  Push(S),
+  Pop,
+  Replace(S),
+  Clear,
+}
+
+// screen hooks called by the transitions, separated from the AppCtx for testing
+trait ScreenHooks<S> {
+  type Entry;
+  fn enter(&mut self, screen: S) -> Self::Entry;
+  fn exit(&mut self, entry: Self::Entry);
+  fn pause(&mut self, entry: &mut Self::Entry);
+  fn resume(&mut self, entry: &mut Self::Entry);
+}
+
+impl<S> ScreenTransition<S> {
+  fn apply<H: ScreenHooks<S>>(self, hooks: &mut H, stack: &mut Vec<H::Entry>) {
+    match self {
+
+      Self::Push(screen) => {
+        if let Some(top) = stack.last_mut() { hooks.pause(top) }
+        stack.push(hooks.enter(screen));
+      },
+
+      Self::Pop => {
+        if let Some(top) = stack.pop() {
+          hooks.exit(top);
+          if let Some(top) = stack.last_mut() { hooks.resume(top) }
+        }
+      },
+
+      Self::Replace(screen) => {
+        if let Some(top) = stack.pop() { hooks.exit(top) }
+        stack.push(hooks.enter(screen));
+      },
+
+      Self::Clear => {
+        while let Some(top) = stack.pop() { hooks.exit(top) }
+      },
+    }
+  }
+}
+
+// top to bottom until consumed, returns if consumed
+fn route_stack<E>(stack: &mut [E], mut event: impl FnMut(&mut E) -> ScreenFlow) -> bool {
+  stack.iter_mut().rev().any(|entry| event(entry) == ScreenFlow::Consume)
+}
+
+struct ScreenEntry<U: EventLike> {
+  screen: BoxScreen<U>,
+  #[cfg(feature = "futures")] entering: Option<AppFutureId>,
+}
+
+
+pub(super) struct ScreenStack<U: EventLike> {
+  stack: Vec<ScreenEntry<U>>,
+  taken: usize, // screens taken out of the stack while calling them
+  transitions: Vec<ScreenTransition<BoxScreen<U>>>,
+}
+
+impl<U: EventLike> ScreenStack<U> {
+
+  pub fn new() -> Self {
+    Self { stack: Vec::new(), taken: 0, transitions: Vec::new() }
+  }
+
+  // take the stack out, so that screens may access the app_ctx freely
+  fn take(app_ctx: &mut AppCtx<U>) -> Vec<ScreenEntry<U>> {
+    let stack = take(&mut app_ctx.screens.stack);
+    app_ctx.screens.taken = stack.len();
+    stack
+  }
+
+  fn restore(app_ctx: &mut AppCtx<U>, stack: Vec<ScreenEntry<U>>) {
+    app_ctx.screens.taken = 0;
+    app_ctx.screens.stack = stack;
+  }
+
+  // returns true if the event was internal to the stack or consumed by a screen
+  pub fn route(app_ctx: &mut AppCtx<U>, event: &Event<U>) -> bool {
+
+    let mut stack = Self::take(app_ctx);
+
+    #[cfg(feature = "futures")]
+    if let Event::FutureReady(id) = event &&
+      let Some(entry) = stack.iter_mut().find(|entry| entry.entering == Some(*id))
+    {
+      entry.entering = None;
+      entry.screen.entered(app_ctx);
+      Self::restore(app_ctx, stack);
+      return true;
+    }
+
+    let consumed = route_stack(&mut stack, |entry| entry.screen.event(app_ctx, event));
+
+    Self::restore(app_ctx, stack);
+    consumed
+  }
+
+  pub fn apply_transitions(app_ctx: &mut AppCtx<U>) {
+
+    while !app_ctx.screens.transitions.is_empty() {
+
+      let transitions = take(&mut app_ctx.screens.transitions);
+      let mut stack = Self::take(app_ctx);
+
+      for transition in transitions {
+        app_ctx.screens.taken = stack.len();
+        transition.apply(app_ctx, &mut stack);
+      }
+
+      Self::restore(app_ctx, stack);
+    }
+  }
+}
+
+impl<U: EventLike> ScreenHooks<BoxScreen<U>> for AppCtx<U> {
+
+  type Entry = ScreenEntry<U>;
+
+  fn enter(&mut self, mut screen: BoxScreen<U>) -> ScreenEntry<U> {
+
+    screen.enter(self);
+
+    ScreenEntry {
+      #[cfg(feature = "futures")]
+      entering: screen.enter_async(self).map(|future| self.futures.spawn(future)),
+      screen,
+    }
+  }
+
+  fn exit(&mut self, mut entry: ScreenEntry<U>) {
+
+    #[cfg(feature = "futures")]
+    if let Some(id) = entry.entering.take() {
+      self.futures.cancel(&id);
+    }
+
+    entry.screen.exit(self);
+  }
+
+  fn pause(&mut self, entry: &mut ScreenEntry<U>) { entry.screen.pause(self) }
+
+  fn resume(&mut self, entry: &mut ScreenEntry<U>) { entry.screen.resume(self) }
+}
+
+
+// app-ctx interface, transitions are applied after the current event
+
+impl<U: EventLike> AppCtx<U> {
+
+  pub fn push_screen(&mut self, screen: impl Screen<U>) {
+    self.screens.transitions.push(ScreenTransition::Push(Box::new(screen)));
+  }
+
+  pub fn pop_screen(&mut self) {
+    self.screens.transitions.push(ScreenTransition::Pop);
+  }
+
+  pub fn replace_screen(&mut self, screen: impl Screen<U>) {
+    self.screens.transitions.push(ScreenTransition::Replace(Box::new(screen)));
+  }
+
+  pub fn clear_screens(&mut self) {
+    self.screens.transitions.push(ScreenTransition::Clear);
+  }
+
+  // includes the screens being called while routing or transitioning
+  pub fn screen_count(&self) -> usize { self.screens.stack.len() + self.screens.taken }
+
+  pub fn has_screen_transitions(&self) -> bool { !self.screens.transitions.is_empty() }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[derive(Default)]
+  struct Log(Vec<String>);
+
+  impl ScreenHooks<&'static str> for Log {
+    type Entry = &'static str;
+    fn enter(&mut self, screen: &'static str) -> &'static str { self.0.push(format!("enter {screen}")); screen }
+    fn exit(&mut self, entry: &'static str) { self.0.push(format!("exit {entry}")) }
+    fn pause(&mut self, entry: &mut &'static str) { self.0.push(format!("pause {entry}")) }
+    fn resume(&mut self, entry: &mut &'static str) { self.0.push(format!("resume {entry}")) }
+  }
+
+  #[test]
+  fn transitions() {
+
+    let mut log = Log::default();
+    let mut stack = Vec::new();
+
+    for transition in [
+      ScreenTransition::Push("a"), ScreenTransition::Push("b"), ScreenTransition::Replace("c"),
+      ScreenTransition::Pop, ScreenTransition::Pop, ScreenTransition::Pop,
+      ScreenTransition::Push("d"), ScreenTransition::Push("e"), ScreenTransition::Clear,
+    ] {
+      transition.apply(&mut log, &mut stack);
+    }
+
+    assert!(stack.is_empty());
+    assert_eq!(log.0, [
+      "enter a", "pause a", "enter b", "exit b", "enter c",
+      "exit c", "resume a", "exit a",
+      "enter d", "pause d", "enter e", "exit e", "exit d",
+    ]);
+  }
+
+  #[test]
+  fn routing() {
+
+    let mut stack = ["bottom", "overlay", "top"];
+    let mut routed = Vec::new();
+
+    // the overlay passes through, the bottom consumes
+    let flow = |screen: &str| if screen == "bottom" { ScreenFlow::Consume } else { ScreenFlow::PassThrough };
+
+    assert!(route_stack(&mut stack, |screen| { routed.push(*screen); flow(screen) }));
+    assert_eq!(routed, ["top", "overlay", "bottom"]);
+
+    assert!(!route_stack(&mut stack[1..], |screen| flow(screen)));
+    assert!(!route_stack::<&str>(&mut [], |_| ScreenFlow::Consume));
+  }
+}
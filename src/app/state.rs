@@ -15,19 +15,20 @@ pub(super) struct AppState<App: AppHandler> {
   #[cfg(feature = "auto_wake_lock")] auto_wake_lock: DetectChanges<bool>,
   window_id: WindowId,
   pub(super) app_ctx: AppCtx<App::UserEvent>,
+  layers: Vec<BoxAppLayer<App::UserEvent>>,
   app: App,
 }
 
 impl<App: AppHandler> AppState<App> {
 
-  pub fn new(app_ctx: AppCtx<App::UserEvent>, app: App) -> Self {
+  pub fn new(app_ctx: AppCtx<App::UserEvent>, layers: Vec<BoxAppLayer<App::UserEvent>>, app: App) -> Self {
     Self {
       #[cfg(feature = "frame_pacing")] redraw_requested: false,
       #[cfg(feature = "auto_wake_lock")] wake_lock: WakeLock::new().map_err(|m| log::warn!("{m:?}")).ok(),
       #[cfg(feature = "auto_wake_lock")] auto_wake_lock: DetectChanges::new(false),
 
       window_id: app_ctx.window.id(),
-      app_ctx, app,
+      app_ctx, layers, app,
     }
   }
 
@@ -38,24 +39,44 @@ impl<App: AppHandler> AppState<App> {
     match event {
 
       AppEvent::Resumed => {
-        self.app.event(app_ctx, Event::Resumed);
+        #[cfg(feature = "app_clock")]
+        app_ctx.clock_suspended(false);
+
+        dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::Resumed);
         self.after_event(None);
       },
 
       AppEvent::Suspended => {
-        self.app.event(app_ctx, Event::Suspended);
+        #[cfg(feature = "app_clock")]
+        app_ctx.clock_suspended(true);
+
+        dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::Suspended);
         self.after_event(None);
       },
 
       #[cfg(feature = "futures")]
       AppEvent::FutureReady {id, output: ()} => {
-        self.app.event(app_ctx, Event::FutureReady(id));
+        dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::FutureReady(id));
         self.after_event(None);
       },
 
       #[cfg(feature = "timeout")]
       AppEvent::Timeout {id: AppTimeoutId::User(id), instant} => {
-        self.app.event(app_ctx, Event::Timeout {id, instant});
+        dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::Timeout {id, instant});
+        self.after_event(None);
+      },
+
+      #[cfg(all(feature = "app_clock", feature = "timeout"))]
+      AppEvent::Timeout {id: AppTimeoutId::AppClock(id), ..} => {
+        if let Some(elapsed) = app_ctx.app_timeout_fired(id) {
+          dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::AppTimeout {id, elapsed});
+        }
+        self.after_event(None);
+      },
+
+      #[cfg(feature = "timeout")]
+      AppEvent::ClockChanged(change) => {
+        dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::ClockChanged(change));
         self.after_event(None);
       },
 
@@ -69,26 +90,41 @@ impl<App: AppHandler> AppState<App> {
         self.redraw_requested = true;
       },
 
+      #[cfg(feature = "monitor_change")]
+      AppEvent::Timeout {id: AppTimeoutId::MonitorCheck(id), ..} if id == self.window_id => {
+        if let Some(info) = app_ctx.check_monitor() {
+          dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::MonitorChanged(info));
+        }
+        app_ctx.schedule_monitor_check();
+        self.after_event(None);
+      },
+
       AppEvent::UserEvent(AppEventExt::UserEvent(event)) => {
-        self.app.event(app_ctx, Event::UserEvent(event));
+        dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::UserEvent(event));
+        self.after_event(None);
+      },
+
+      #[cfg(feature = "event_bus")]
+      AppEvent::UserEvent(AppEventExt::BusMessage(message)) => {
+        EventBus::deliver(app_ctx, message);
         self.after_event(None);
       },
 
       #[cfg(feature = "device_events")]
       AppEvent::DeviceEvent {device_id, event} => {
-        self.app.event(app_ctx, Event::DeviceEvent {device_id, event});
+        dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::DeviceEvent {device_id, event});
         self.after_event(None);
       },
 
       #[cfg(all(feature = "web_clipboard", target_family="wasm"))]
       AppEvent::UserEvent(AppEventExt::ClipboardFetch(id)) if id == self.window_id => {
-        self.app.event(app_ctx, Event::ClipboardFetch);
+        dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::ClipboardFetch);
         self.after_event(None);
       },
 
       #[cfg(all(feature = "web_clipboard", target_family="wasm"))]
       AppEvent::UserEvent(AppEventExt::ClipboardPaste(id)) if id == self.window_id  => {
-        self.app.event(app_ctx, Event::ClipboardPaste);
+        dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::ClipboardPaste);
         self.after_event(None);
       },
 
@@ -97,12 +133,28 @@ impl<App: AppHandler> AppState<App> {
         #[cfg(feature = "auto_wake_lock")]
         let mut focus_change: Option<bool> = None;
 
+        #[cfg(feature = "input")]
+        app_ctx.input.update(&window_event);
+
+        #[cfg(feature = "power_saving")]
+        app_ctx.power_window_event(&window_event);
+
+        #[cfg(feature = "app_clock")]
+        if let WindowEvent::Focused(focused) = window_event { app_ctx.clock_focused(focused) }
+
+        #[cfg(feature = "input")]
+        let end_frame = matches!(window_event, WindowEvent::RedrawRequested);
+
         // before user handler
         match &window_event {
 
           #[cfg(feature = "frame_pacing")]
           WindowEvent::RedrawRequested => {
 
+            // frames following a frame request count as continuous rendering,
+            // missed frames are counted against the throttled rate while power saving
+            app_ctx.frame_stats.add_frame(Instant::now(), app_ctx.target_frame_duration(), app_ctx.frame_timeout.is_some());
+
             if app_ctx.frame_timeout.is_none() || !self.redraw_requested {
               // no frame was requested or frame_request-timeout is still in progress
               app_ctx.frame_time = Instant::now();
@@ -116,7 +168,7 @@ impl<App: AppHandler> AppState<App> {
             app_ctx.window.request_redraw();
           },
 
-          #[cfg(feature = "frame_pacing")]
+          #[cfg(all(feature = "frame_pacing", not(feature = "monitor_change")))]
           WindowEvent::Moved(_) => {
             app_ctx.fetch_monitor_frame_duration();
           },
@@ -131,8 +183,32 @@ impl<App: AppHandler> AppState<App> {
           _ => {},
         }
 
+        #[cfg(feature = "monitor_change")]
+        if let Some(info) = match window_event {
+          WindowEvent::Moved(_) | WindowEvent::Resized(_) | WindowEvent::ScaleFactorChanged {..} => app_ctx.check_monitor(),
+          WindowEvent::Occluded(occluded) => app_ctx.monitor_occluded(occluded),
+          _ => None,
+        } {
+          dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::MonitorChanged(info));
+        }
+
+        #[cfg(feature = "animations")]
+        if matches!(window_event, WindowEvent::RedrawRequested) { Animations::advance(app_ctx) }
+
+        // regions are registered anew while handling the redraw
+        #[cfg(feature = "regions")]
+        if matches!(window_event, WindowEvent::RedrawRequested) { app_ctx.regions.clear() }
+
+        #[cfg(feature = "regions")]
+        for region_event in app_ctx.regions.update(&window_event) {
+          dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::Region(region_event));
+        }
+
         // exec event handler
-        self.app.event(app_ctx, Event::WindowEvent(window_event));
+        dispatch_event(&mut self.layers, &mut self.app, app_ctx, Event::WindowEvent(window_event));
+
+        #[cfg(feature = "input")]
+        if end_frame { app_ctx.input.end_frame() }
 
         self.after_event({
           #[cfg(feature = "auto_wake_lock")] { focus_change }
@@ -150,6 +226,9 @@ impl<App: AppHandler> AppState<App> {
     #[allow(unused)]
     let app_ctx = &mut self.app_ctx;
 
+    #[cfg(feature = "screens")]
+    ScreenStack::apply_transitions(app_ctx);
+
     #[cfg(feature = "frame_pacing")]
     if app_ctx.schedule_frame {
 
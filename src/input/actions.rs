@@ -0,0 +1,542 @@
+use winit::{
+    event::{WindowEvent, KeyEvent, ElementState, MouseButton, TouchPhase, Touch},
+    keyboard::{PhysicalKey, KeyCode, ModifiersState},
+};
+use std::{fmt, str::FromStr, vec::Drain};
+use anyhow::{Result as Res, Error, anyhow, bail};
+#[cfg(feature = "touches")] use crate::touches::{GestureKind, GestureEvent, GesturePhase};
+
+
+// ctrl on most platforms, command (super) on apple platforms
+pub fn primary_modifier() -> ModifiersState {
+    if cfg!(any(target_os="macos", target_os="ios")) { ModifiersState::SUPER }
+    else { ModifiersState::CONTROL }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Touch(u8), // number of fingers down
+    #[cfg(feature = "touches")] Gesture(GestureKind), // recognized by a touches::GestureArena
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub modifiers: ModifiersState,
+    pub primary: bool,
+}
+
+impl Chord {
+    pub fn resolve(&self) -> ModifiersState {
+        if self.primary { self.modifiers | primary_modifier() } else { self.modifiers }
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Binding {
+    pub trigger: Trigger,
+    pub chord: Chord,
+}
+
+impl Binding {
+
+    pub fn new(trigger: Trigger) -> Self { Self { trigger, chord: Chord::default() } }
+
+    pub fn key(code: KeyCode) -> Self { Self::new(Trigger::Key(code)) }
+    pub fn mouse(button: MouseButton) -> Self { Self::new(Trigger::Mouse(button)) }
+    pub fn touch(fingers: u8) -> Self { Self::new(Trigger::Touch(fingers)) }
+    #[cfg(feature = "touches")]
+    pub fn gesture(kind: GestureKind) -> Self { Self::new(Trigger::Gesture(kind)) }
+
+    pub fn with_modifiers(mut self, modifiers: ModifiersState) -> Self { self.chord.modifiers |= modifiers; self }
+    pub fn with_primary(mut self) -> Self { self.chord.primary = true; self }
+    pub fn with_shift(self) -> Self { self.with_modifiers(ModifiersState::SHIFT) }
+    pub fn with_control(self) -> Self { self.with_modifiers(ModifiersState::CONTROL) }
+    pub fn with_alt(self) -> Self { self.with_modifiers(ModifiersState::ALT) }
+    pub fn with_super(self) -> Self { self.with_modifiers(ModifiersState::SUPER) }
+
+    // same input on this platform
+    pub fn conflicts_with(&self, other: &Self) -> bool {
+        self.trigger == other.trigger && self.chord.resolve() == other.chord.resolve()
+    }
+
+    fn matches(&self, trigger: Trigger, modifiers: ModifiersState) -> bool {
+        self.trigger == trigger && modifiers.contains(self.chord.resolve())
+    }
+}
+
+
+// text representation, e.g. "Primary+Shift+KeyS", "Mouse:Left", "Touch:2" or "Gesture:Pinch"
+
+const MODIFIER_NAMES: [(&str, ModifiersState); 4] = [
+    ("Shift", ModifiersState::SHIFT), ("Ctrl", ModifiersState::CONTROL),
+    ("Alt", ModifiersState::ALT), ("Super", ModifiersState::SUPER),
+];
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+        if self.chord.primary { write!(f, "Primary+")? }
+
+        for (name, modifier) in MODIFIER_NAMES {
+            if self.chord.modifiers.contains(modifier) { write!(f, "{name}+")? }
+        }
+
+        match self.trigger {
+            Trigger::Key(code) => write!(f, "{code:?}"),
+            Trigger::Mouse(MouseButton::Other(n)) => write!(f, "Mouse:{n}"),
+            Trigger::Mouse(button) => write!(f, "Mouse:{button:?}"),
+            Trigger::Touch(fingers) => write!(f, "Touch:{fingers}"),
+            #[cfg(feature = "touches")]
+            Trigger::Gesture(kind) => write!(f, "Gesture:{kind:?}"),
+        }
+    }
+}
+
+impl FromStr for Binding {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Res<Self> {
+
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let trigger = parts.pop().filter(|part| !part.is_empty()).ok_or_else(|| anyhow!("empty binding {text:?}"))?;
+
+        let trigger = if let Some(button) = trigger.strip_prefix("Mouse:") {
+            Trigger::Mouse(match button {
+                "Left" => MouseButton::Left, "Right" => MouseButton::Right, "Middle" => MouseButton::Middle,
+                "Back" => MouseButton::Back, "Forward" => MouseButton::Forward,
+                other => MouseButton::Other(other.parse().map_err(|_| anyhow!("unknown mouse button {other:?}"))?),
+            })
+        }
+        else if let Some(fingers) = trigger.strip_prefix("Touch:") {
+            Trigger::Touch(fingers.parse().map_err(|_| anyhow!("invalid finger count {fingers:?}"))?)
+        }
+        else if let Some(kind) = trigger.strip_prefix("Gesture:") {
+            gesture_trigger(kind)?
+        }
+        else {
+            Trigger::Key(key_code_from_str(trigger).ok_or_else(|| anyhow!("unknown key {trigger:?}"))?)
+        };
+
+        let mut binding = Self::new(trigger);
+
+        for part in parts {
+            if part == "Primary" {
+                binding.chord.primary = true;
+            }
+            else if let Some((_, modifier)) = MODIFIER_NAMES.iter().find(|(name, _)| *name == part) {
+                binding.chord.modifiers |= *modifier;
+            }
+            else { bail!("unknown modifier {part:?}") }
+        }
+
+        Ok(binding)
+    }
+}
+
+
+#[cfg(feature = "touches")]
+fn gesture_trigger(kind: &str) -> Res<Trigger> {
+    use GestureKind::*;
+    Ok(Trigger::Gesture(match kind {
+        "Tap" => Tap, "DoubleTap" => DoubleTap, "LongPress" => LongPress, "Pan" => Pan,
+        "Swipe" => Swipe, "Pinch" => Pinch, "Rotate" => Rotate,
+        other => bail!("unknown gesture {other:?}"),
+    }))
+}
+
+#[cfg(not(feature = "touches"))]
+fn gesture_trigger(kind: &str) -> Res<Trigger> {
+    bail!("gesture {kind:?} needs the touches feature")
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionPhase {
+    Pressed,
+    Held, // key repeat
+    Released,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionEvent<A> {
+    pub action: A,
+    pub phase: ActionPhase,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ActionMap<A: Clone + PartialEq> {
+    bindings: Vec<(A, Binding)>,
+    modifiers: ModifiersState,
+    touches: Vec<u64>,
+    active: Vec<(Trigger, A)>, // actions with the trigger which activated them
+    events: Vec<ActionEvent<A>>,
+}
+
+impl<A: Clone + PartialEq> Default for ActionMap<A> {
+    fn default() -> Self {
+        Self { bindings: Vec::new(), modifiers: ModifiersState::empty(), touches: Vec::new(), active: Vec::new(), events: Vec::new() }
+    }
+}
+
+impl<A: Clone + PartialEq> ActionMap<A> {
+
+    pub fn new() -> Self { Self::default() }
+
+    // bindings
+
+    // returns other actions with a conflicting binding, the binding is added nonetheless
+    pub fn bind(&mut self, action: A, binding: Binding) -> Vec<A> {
+
+        let conflicts = self.bindings.iter()
+            .filter(|(other, other_binding)| *other != action && other_binding.conflicts_with(&binding))
+            .map(|(other, _)| other.clone())
+            .collect()
+        ;
+
+        if !self.bindings.iter().any(|(other, other_binding)| *other == action && *other_binding == binding) {
+            self.bindings.push((action, binding));
+        }
+
+        conflicts
+    }
+
+    pub fn unbind(&mut self, action: &A, binding: &Binding) {
+        self.bindings.retain(|(other, other_binding)| !(other == action && other_binding == binding));
+    }
+
+    pub fn unbind_action(&mut self, action: &A) {
+        self.bindings.retain(|(other, _)| other != action);
+    }
+
+    pub fn clear(&mut self) {
+        self.release_all();
+        self.bindings.clear();
+    }
+
+    pub fn bindings(&self) -> impl Iterator<Item=&(A, Binding)> { self.bindings.iter() }
+
+    pub fn bindings_of<'a>(&'a self, action: &'a A) -> impl Iterator<Item=&'a Binding> {
+        self.bindings.iter().filter_map(move |(other, binding)| (other == action).then_some(binding))
+    }
+
+    // all bindings which are bound to more than one action
+    pub fn conflicts(&self) -> Vec<(Binding, Vec<A>)> {
+
+        let mut conflicts: Vec<(Binding, Vec<A>)> = Vec::new();
+
+        for (i, (action, binding)) in self.bindings.iter().enumerate() {
+
+            if conflicts.iter().any(|(other, _)| other.conflicts_with(binding)) { continue }
+
+            let mut actions = vec![action.clone()];
+
+            for (other, other_binding) in &self.bindings[i+1..] {
+                if other_binding.conflicts_with(binding) && !actions.contains(other) {
+                    actions.push(other.clone());
+                }
+            }
+
+            if actions.len() > 1 { conflicts.push((*binding, actions)) }
+        }
+
+        conflicts
+    }
+
+
+    // state
+
+    pub fn is_active(&self, action: &A) -> bool { self.active.iter().any(|(_, other)| other == action) }
+
+    pub fn active(&self) -> impl Iterator<Item=&A> {
+        self.active.iter().enumerate()
+            .filter(|(i, (_, action))| !self.active[..*i].iter().any(|(_, other)| other == action))
+            .map(|(_, (_, action))| action)
+    }
+
+    pub fn update(&mut self, event: &WindowEvent) -> Drain<'_, ActionEvent<A>> {
+        match event {
+
+            WindowEvent::KeyboardInput { event: KeyEvent { physical_key: PhysicalKey::Code(code), state, repeat, .. }, .. } => {
+                match state {
+                    ElementState::Pressed if *repeat => self.repeat(Trigger::Key(*code)),
+                    ElementState::Pressed => self.press(Trigger::Key(*code)),
+                    ElementState::Released => self.release(Trigger::Key(*code)),
+                }
+            },
+
+            WindowEvent::MouseInput { state, button, .. } => match state {
+                ElementState::Pressed => self.press(Trigger::Mouse(*button)),
+                ElementState::Released => self.release(Trigger::Mouse(*button)),
+            },
+
+            WindowEvent::Touch(Touch { id, phase, .. }) => self.touch(*id, *phase),
+
+            WindowEvent::ModifiersChanged(modifiers) => self.modifiers = modifiers.state(),
+
+            WindowEvent::Focused(false) => {
+                self.release_all();
+                self.modifiers = ModifiersState::empty();
+            },
+
+            _ => {},
+        }
+
+        self.events.drain(..)
+    }
+
+    // discrete gestures are pressed and released at once, continuous ones are held until they end
+    #[cfg(feature = "touches")]
+    pub fn gesture(&mut self, event: &GestureEvent) -> Drain<'_, ActionEvent<A>> {
+
+        let trigger = Trigger::Gesture(event.gesture.kind());
+
+        match event.phase {
+            GesturePhase::Recognized => { self.press(trigger); self.release(trigger) },
+            GesturePhase::Began => self.press(trigger),
+            GesturePhase::Changed => {},
+            GesturePhase::Ended | GesturePhase::Cancelled => self.release(trigger),
+        }
+
+        self.events.drain(..)
+    }
+
+    pub fn release_all(&mut self) {
+        self.touches.clear();
+        while let Some((trigger, _)) = self.active.first() {
+            self.release(*trigger);
+        }
+    }
+
+    fn press(&mut self, trigger: Trigger) {
+
+        // only the most specific chords apply, e.g. Ctrl+S instead of S
+        let Some(specificity) = self.bindings.iter()
+            .filter(|(_, binding)| binding.matches(trigger, self.modifiers))
+            .map(|(_, binding)| binding.chord.resolve().bits().count_ones())
+            .max()
+        else { return };
+
+        for (action, binding) in &self.bindings {
+            if binding.matches(trigger, self.modifiers) && binding.chord.resolve().bits().count_ones() == specificity {
+
+                if self.active.iter().any(|(other_trigger, other)| *other_trigger == trigger && other == action) {
+                    continue;
+                }
+
+                if !self.active.iter().any(|(_, other)| other == action) {
+                    self.events.push(ActionEvent { action: action.clone(), phase: ActionPhase::Pressed });
+                }
+
+                self.active.push((trigger, action.clone()));
+            }
+        }
+    }
+
+    fn repeat(&mut self, trigger: Trigger) {
+        for (other_trigger, action) in &self.active {
+            if *other_trigger == trigger {
+                self.events.push(ActionEvent { action: action.clone(), phase: ActionPhase::Held });
+            }
+        }
+    }
+
+    fn release(&mut self, trigger: Trigger) {
+
+        let mut i = 0;
+
+        while i < self.active.len() {
+            if self.active[i].0 == trigger {
+                let (_, action) = self.active.remove(i);
+                if !self.active.iter().any(|(_, other)| *other == action) {
+                    self.events.push(ActionEvent { action, phase: ActionPhase::Released });
+                }
+            }
+            else { i += 1 }
+        }
+    }
+
+    fn touch(&mut self, id: u64, phase: TouchPhase) {
+
+        let fingers = u8::try_from(self.touches.len()).unwrap_or(u8::MAX);
+
+        match phase {
+            TouchPhase::Started if !self.touches.contains(&id) => {
+                self.touches.push(id);
+                self.release(Trigger::Touch(fingers));
+                self.press(Trigger::Touch(fingers.saturating_add(1)));
+            },
+            TouchPhase::Ended | TouchPhase::Cancelled if self.touches.contains(&id) => {
+                self.touches.retain(|other| *other != id);
+                self.release(Trigger::Touch(fingers));
+            },
+            _ => {},
+        }
+    }
+}
+
+
+// persistence as lines of "action = binding"
+
+impl<A: Clone + PartialEq + fmt::Display + FromStr> ActionMap<A> {
+
+    pub fn to_config(&self) -> String {
+        self.bindings.iter().map(|(action, binding)| format!("{action} = {binding}\n")).collect()
+    }
+
+    // replaces all bindings
+    pub fn load_config(&mut self, config: &str) -> Res<()> {
+
+        let mut bindings = Vec::new();
+
+        for line in config.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+
+            let (action, binding) = line.split_once('=').ok_or_else(|| anyhow!("invalid binding line {line:?}"))?;
+
+            let action = action.trim().parse().map_err(|_| anyhow!("unknown action {:?}", action.trim()))?;
+            bindings.push((action, binding.parse()?));
+        }
+
+        self.clear();
+
+        for (action, binding) in bindings {
+            self.bind(action, binding);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature="storage")]
+    pub fn save(&self, storage: &crate::storage::LocalStorage, key: &str) -> Res<()> {
+        storage.set(key, &self.to_config())
+    }
+
+    // returns false if nothing was stored yet, keeping the current bindings
+    #[cfg(feature="storage")]
+    pub fn load(&mut self, storage: &crate::storage::LocalStorage, key: &str) -> Res<bool> {
+        match storage.get(key)? {
+            Some(config) => self.load_config(&config).map(|_| true),
+            None => Ok(false),
+        }
+    }
+}
+
+
+macro_rules! key_codes {
+    ($($name:ident,)+) => {
+        fn key_code_from_str(name: &str) -> Option<KeyCode> {
+            match name {
+                $( stringify!($name) => Some(KeyCode::$name), )+
+                _ => None,
+            }
+        }
+    };
+}
+
+key_codes! {
+    Backquote, Backslash, BracketLeft, BracketRight, Comma, Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6,
+    Digit7, Digit8, Digit9, Equal, IntlBackslash, IntlRo, IntlYen, KeyA, KeyB, KeyC, KeyD, KeyE,
+    KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM, KeyN, KeyO, KeyP, KeyQ,
+    KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ, Minus, Period, Quote,
+    Semicolon, Slash, AltLeft, AltRight, Backspace, CapsLock, ContextMenu, ControlLeft, ControlRight, Enter, SuperLeft, SuperRight,
+    ShiftLeft, ShiftRight, Space, Tab, Convert, KanaMode, Lang1, Lang2, Lang3, Lang4, Lang5, NonConvert,
+    Delete, End, Help, Home, Insert, PageDown, PageUp, ArrowDown, ArrowLeft, ArrowRight, ArrowUp, NumLock,
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9, NumpadAdd, NumpadBackspace,
+    NumpadClear, NumpadClearEntry, NumpadComma, NumpadDecimal, NumpadDivide, NumpadEnter, NumpadEqual, NumpadHash, NumpadMemoryAdd, NumpadMemoryClear, NumpadMemoryRecall, NumpadMemoryStore,
+    NumpadMemorySubtract, NumpadMultiply, NumpadParenLeft, NumpadParenRight, NumpadStar, NumpadSubtract, Escape, Fn, FnLock, PrintScreen, ScrollLock, Pause,
+    BrowserBack, BrowserFavorites, BrowserForward, BrowserHome, BrowserRefresh, BrowserSearch, BrowserStop, Eject, LaunchApp1, LaunchApp2, LaunchMail, MediaPlayPause,
+    MediaSelect, MediaStop, MediaTrackNext, MediaTrackPrevious, Power, Sleep, AudioVolumeDown, AudioVolumeMute, AudioVolumeUp, WakeUp, Meta, Hyper,
+    Turbo, Abort, Resume, Suspend, Again, Copy, Cut, Find, Open, Paste, Props, Select,
+    Undo, Hiragana, Katakana, F1, F2, F3, F4, F5, F6, F7, F8, F9,
+    F10, F11, F12, F13, F14, F15, F16, F17, F18, F19, F20, F21,
+    F22, F23, F24, F25, F26, F27, F28, F29, F30, F31, F32, F33,
+    F34, F35,
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn binding_text_round_trip() {
+        for binding in [
+            Binding::key(KeyCode::KeyS).with_primary().with_shift(),
+            Binding::mouse(MouseButton::Other(7)).with_alt(),
+            Binding::touch(2),
+            #[cfg(feature = "touches")] Binding::gesture(GestureKind::DoubleTap).with_shift(),
+        ] {
+            assert_eq!(binding.to_string().parse::<Binding>().unwrap(), binding);
+        }
+        assert!("Shift+Hyperdrive".parse::<Binding>().is_err());
+    }
+
+    #[test]
+    fn most_specific_chord_wins() {
+
+        let mut map = ActionMap::new();
+        map.bind("move", Binding::key(KeyCode::KeyS));
+        map.bind("save", Binding::key(KeyCode::KeyS).with_primary());
+
+        map.modifiers = primary_modifier();
+        map.press(Trigger::Key(KeyCode::KeyS));
+        assert_eq!(map.events.drain(..).collect::<Vec<_>>(), [ActionEvent { action: "save", phase: ActionPhase::Pressed }]);
+
+        map.modifiers = ModifiersState::empty();
+        map.release(Trigger::Key(KeyCode::KeyS));
+        assert_eq!(map.events.drain(..).collect::<Vec<_>>(), [ActionEvent { action: "save", phase: ActionPhase::Released }]);
+
+        map.press(Trigger::Key(KeyCode::KeyS));
+        assert!(map.is_active(&"move") && !map.is_active(&"save"));
+    }
+
+    #[test]
+    fn conflicts_are_detected() {
+
+        let mut map = ActionMap::new();
+        assert!(map.bind("jump", Binding::key(KeyCode::Space)).is_empty());
+        assert_eq!(map.bind("fire", Binding::key(KeyCode::Space)), ["jump"]);
+        assert_eq!(map.conflicts(), [(Binding::key(KeyCode::Space), vec!["jump", "fire"])]);
+    }
+
+    #[test]
+    fn many_touches() {
+
+        let mut map = ActionMap::new();
+        map.bind("crowd", Binding::touch(u8::MAX));
+
+        for id in 0..300 { map.touch(id, TouchPhase::Started) }
+        assert!(map.is_active(&"crowd"));
+
+        for id in 0..300 { map.touch(id, TouchPhase::Ended) }
+        assert!(!map.is_active(&"crowd"));
+    }
+
+    #[cfg(feature = "touches")]
+    #[test]
+    fn gesture_triggers() {
+
+        use crate::{touches::{Gesture, TouchPos}, time::Instant};
+
+        let mut map = ActionMap::new();
+        map.bind("select", Binding::gesture(GestureKind::Tap));
+        map.bind("zoom", Binding::gesture(GestureKind::Pinch));
+
+        let event = |phase, gesture| GestureEvent { phase, gesture, instant: Instant::now() };
+        let tap = Gesture::Tap { location: TouchPos::ZERO, fingers: 1 };
+        let pinch = Gesture::Pinch { center: TouchPos::ZERO, scale: 1.0, delta_scale: 1.0 };
+
+        assert_eq!(map.gesture(&event(GesturePhase::Recognized, tap)).map(|event| event.phase).collect::<Vec<_>>(), [ActionPhase::Pressed, ActionPhase::Released]);
+
+        assert_eq!(map.gesture(&event(GesturePhase::Began, pinch)).collect::<Vec<_>>(), [ActionEvent { action: "zoom", phase: ActionPhase::Pressed }]);
+        assert_eq!(map.gesture(&event(GesturePhase::Changed, pinch)).count(), 0);
+        assert!(map.is_active(&"zoom"));
+        assert_eq!(map.gesture(&event(GesturePhase::Ended, pinch)).collect::<Vec<_>>(), [ActionEvent { action: "zoom", phase: ActionPhase::Released }]);
+    }
+}
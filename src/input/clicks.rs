@@ -0,0 +1,224 @@
+use winit::{event::{WindowEvent, ElementState, MouseButton}, dpi::PhysicalPosition};
+use std::vec::Drain;
+use crate::time::{Instant, Duration};
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClickConfig {
+    pub multi_click_time: Duration, // between presses
+    pub multi_click_distance: f64, // physical pixels from the previous press
+    pub drag_threshold: f64, // physical pixels from the press
+}
+
+impl Default for ClickConfig {
+    fn default() -> Self {
+        Self {
+            multi_click_time: Duration::from_millis(500),
+            multi_click_distance: 4.0,
+            drag_threshold: 4.0,
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClickEvent {
+    // count is 2 for the second press of a double click, etc.
+    Press { button: MouseButton, count: u32, position: PhysicalPosition<f64> },
+    // released without dragging
+    Click { button: MouseButton, count: u32, position: PhysicalPosition<f64> },
+    DragStart { button: MouseButton, origin: PhysicalPosition<f64>, position: PhysicalPosition<f64> },
+    DragMove { button: MouseButton, origin: PhysicalPosition<f64>, position: PhysicalPosition<f64> },
+    // cancelled on focus loss, when the release will be missed
+    DragEnd { button: MouseButton, origin: PhysicalPosition<f64>, position: PhysicalPosition<f64>, cancelled: bool },
+}
+
+
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    button: MouseButton,
+    origin: PhysicalPosition<f64>,
+    instant: Instant,
+    count: u32,
+    dragging: bool,
+}
+
+
+// tracks the first held button only, other buttons pressed meanwhile are ignored
+
+#[derive(Debug, Clone, Default)]
+pub struct ClickTracker {
+    pub config: ClickConfig,
+    cursor: Option<PhysicalPosition<f64>>,
+    pressed: Option<Press>,
+    last_press: Option<Press>,
+    events: Vec<ClickEvent>,
+}
+
+impl ClickTracker {
+
+    pub fn new(config: ClickConfig) -> Self { Self { config, ..Default::default() } }
+
+    pub fn is_dragging(&self) -> bool { self.pressed.is_some_and(|press| press.dragging) }
+
+    pub fn pressed_button(&self) -> Option<MouseButton> { self.pressed.map(|press| press.button) }
+
+    pub fn update(&mut self, event: &WindowEvent) -> Drain<'_, ClickEvent> {
+        self.update_at(event, Instant::now())
+    }
+
+    pub fn update_at(&mut self, event: &WindowEvent, instant: Instant) -> Drain<'_, ClickEvent> {
+
+        match event {
+
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor = Some(*position);
+
+                if let Some(press) = &mut self.pressed {
+
+                    if !press.dragging && distance(press.origin, *position) > self.config.drag_threshold {
+                        press.dragging = true;
+                        self.events.push(ClickEvent::DragStart { button: press.button, origin: press.origin, position: *position });
+                    }
+
+                    if press.dragging {
+                        self.events.push(ClickEvent::DragMove { button: press.button, origin: press.origin, position: *position });
+                    }
+                }
+            },
+
+            WindowEvent::MouseInput { state: ElementState::Pressed, button, .. } if self.pressed.is_none() => {
+                if let Some(position) = self.cursor {
+
+                    let count = match self.last_press {
+                        Some(last) if
+                            last.button == *button &&
+                            instant.saturating_duration_since(last.instant) <= self.config.multi_click_time &&
+                            distance(last.origin, position) <= self.config.multi_click_distance
+                        => last.count + 1,
+                        _ => 1,
+                    };
+
+                    let press = Press { button: *button, origin: position, instant, count, dragging: false };
+
+                    self.pressed = Some(press);
+                    self.last_press = Some(press);
+
+                    self.events.push(ClickEvent::Press { button: *button, count, position });
+                }
+            },
+
+            WindowEvent::MouseInput { state: ElementState::Released, button, .. } => {
+                if let Some(press) = self.pressed.filter(|press| press.button == *button) {
+
+                    self.pressed = None;
+                    let position = self.cursor.unwrap_or(press.origin);
+
+                    self.events.push(if press.dragging {
+                        // a drag does not count towards multi clicks
+                        self.last_press = None;
+                        ClickEvent::DragEnd { button: press.button, origin: press.origin, position, cancelled: false }
+                    } else {
+                        ClickEvent::Click { button: press.button, count: press.count, position }
+                    });
+                }
+            },
+
+            WindowEvent::Focused(false) => {
+                if let Some(press) = self.pressed.take() && press.dragging {
+                    let position = self.cursor.unwrap_or(press.origin);
+                    self.events.push(ClickEvent::DragEnd { button: press.button, origin: press.origin, position, cancelled: true });
+                }
+                self.last_press = None;
+            },
+
+            _ => {},
+        }
+
+        self.events.drain(..)
+    }
+}
+
+
+fn distance(a: PhysicalPosition<f64>, b: PhysicalPosition<f64>) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use winit::event::DeviceId;
+
+    const DEVICE_ID: DeviceId = DeviceId::dummy();
+
+    fn moved(x: f64, y: f64) -> WindowEvent {
+        WindowEvent::CursorMoved { device_id: DEVICE_ID, position: PhysicalPosition::new(x, y) }
+    }
+
+    fn button(state: ElementState, button: MouseButton) -> WindowEvent {
+        WindowEvent::MouseInput { device_id: DEVICE_ID, state, button }
+    }
+
+    #[test]
+    fn click_counts() {
+
+        let mut clicks = ClickTracker::default();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        let click = |clicks: &mut ClickTracker, ms| {
+            clicks.update_at(&button(ElementState::Pressed, MouseButton::Left), at(ms)).for_each(drop);
+            clicks.update_at(&button(ElementState::Released, MouseButton::Left), at(ms + 50)).collect::<Vec<_>>()
+        };
+
+        clicks.update_at(&moved(10.0, 10.0), at(0)).for_each(drop);
+
+        let pos = PhysicalPosition::new(10.0, 10.0);
+
+        assert_eq!(click(&mut clicks, 0), [ClickEvent::Click { button: MouseButton::Left, count: 1, position: pos }]);
+        assert_eq!(click(&mut clicks, 300), [ClickEvent::Click { button: MouseButton::Left, count: 2, position: pos }]);
+        assert_eq!(click(&mut clicks, 600), [ClickEvent::Click { button: MouseButton::Left, count: 3, position: pos }]);
+
+        // too late
+        assert_eq!(click(&mut clicks, 1200), [ClickEvent::Click { button: MouseButton::Left, count: 1, position: pos }]);
+
+        // too far
+        clicks.update_at(&moved(20.0, 10.0), at(1300)).for_each(drop);
+        assert_eq!(click(&mut clicks, 1300), [ClickEvent::Click { button: MouseButton::Left, count: 1, position: PhysicalPosition::new(20.0, 10.0) }]);
+
+        // other button
+        let events: Vec<_> = clicks.update_at(&button(ElementState::Pressed, MouseButton::Right), at(1400)).collect();
+        assert_eq!(events, [ClickEvent::Press { button: MouseButton::Right, count: 1, position: PhysicalPosition::new(20.0, 10.0) }]);
+    }
+
+    #[test]
+    fn drag_after_threshold() {
+
+        let mut clicks = ClickTracker::default();
+        let start = Instant::now();
+
+        let origin = PhysicalPosition::new(0.0, 0.0);
+
+        clicks.update_at(&moved(0.0, 0.0), start).for_each(drop);
+        clicks.update_at(&button(ElementState::Pressed, MouseButton::Middle), start).for_each(drop);
+
+        assert_eq!(clicks.update_at(&moved(3.0, 0.0), start).count(), 0); // within threshold
+
+        let events: Vec<_> = clicks.update_at(&moved(6.0, 0.0), start).collect();
+        assert_eq!(events, [
+            ClickEvent::DragStart { button: MouseButton::Middle, origin, position: PhysicalPosition::new(6.0, 0.0) },
+            ClickEvent::DragMove { button: MouseButton::Middle, origin, position: PhysicalPosition::new(6.0, 0.0) },
+        ]);
+        assert!(clicks.is_dragging());
+
+        // other buttons are ignored while held
+        assert_eq!(clicks.update_at(&button(ElementState::Released, MouseButton::Left), start).count(), 0);
+
+        let events: Vec<_> = clicks.update_at(&button(ElementState::Released, MouseButton::Middle), start).collect();
+        assert_eq!(events, [ClickEvent::DragEnd { button: MouseButton::Middle, origin, position: PhysicalPosition::new(6.0, 0.0), cancelled: false }]);
+        assert!(!clicks.is_dragging());
+    }
+}
@@ -0,0 +1,12 @@
+
+mod tracker;
+pub use tracker::*;
+
+mod actions;
+pub use actions::*;
+
+mod scroll;
+pub use scroll::*;
+
+mod clicks;
+pub use clicks::*;
@@ -0,0 +1,199 @@
+use winit::event::{WindowEvent, MouseScrollDelta, TouchPhase};
+use crate::time::{Instant, Duration};
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScrollConfig {
+    pub line_height: f32, // logical pixels per line
+    pub scale_factor: f64,
+    pub invert: bool,
+    pub momentum: bool, // off where the platform already synthesizes momentum events
+    pub decay: f32, // rate per second
+    pub min_speed: f32, // logical pixels per second
+    pub velocity_window: Duration,
+}
+
+impl Default for ScrollConfig {
+    fn default() -> Self {
+        Self {
+            line_height: 40.0,
+            scale_factor: 1.0,
+            invert: false,
+            momentum: !cfg!(any(target_os = "macos", target_os = "ios")),
+            decay: 4.0,
+            min_speed: 20.0,
+            velocity_window: Duration::from_millis(100),
+        }
+    }
+}
+
+
+// normalizes wheel events into logical pixels, accumulated per frame,
+// continues pixel scrolls with momentum after the gesture ended
+
+#[derive(Debug, Clone, Default)]
+pub struct ScrollState {
+    pub config: ScrollConfig,
+    frame: [f32; 2],
+    samples: Vec<(Instant, [f32; 2])>,
+    momentum: Option<([f32; 2], Instant)>, // velocity and time of the last advance
+}
+
+impl ScrollState {
+
+    pub fn new(config: ScrollConfig) -> Self { Self { config, ..Default::default() } }
+
+    pub fn normalize(&self, delta: MouseScrollDelta) -> [f32; 2] {
+        let [x, y] = match delta {
+            MouseScrollDelta::LineDelta(x, y) => [x * self.config.line_height, y * self.config.line_height],
+            MouseScrollDelta::PixelDelta(pos) => {
+                let pos = pos.to_logical::<f64>(self.config.scale_factor);
+                [pos.x as f32, pos.y as f32]
+            },
+        };
+        if self.config.invert { [-x, -y] } else { [x, y] }
+    }
+
+    pub fn update(&mut self, event: &WindowEvent) { self.update_at(event, Instant::now()) }
+
+    pub fn update_at(&mut self, event: &WindowEvent, instant: Instant) {
+        match event {
+
+            WindowEvent::MouseWheel { delta, phase, .. } => {
+
+                let pixels = self.normalize(*delta);
+
+                self.frame[0] += pixels[0];
+                self.frame[1] += pixels[1];
+
+                match phase {
+                    TouchPhase::Started => {
+                        self.stop();
+                        self.samples.clear();
+                    },
+                    TouchPhase::Moved => {
+                        // a new gesture or wheel movement interrupts momentum
+                        self.stop();
+                        if matches!(delta, MouseScrollDelta::PixelDelta(_)) {
+                            let window = self.config.velocity_window;
+                            self.samples.retain(|(other, _)| instant.saturating_duration_since(*other) <= window);
+                            self.samples.push((instant, pixels));
+                        }
+                    },
+                    TouchPhase::Ended => {
+                        if self.config.momentum {
+                            let velocity = self.velocity(instant);
+                            if velocity[0].hypot(velocity[1]) >= self.config.min_speed {
+                                self.momentum = Some((velocity, instant));
+                            }
+                        }
+                        self.samples.clear();
+                    },
+                    TouchPhase::Cancelled => {
+                        self.stop();
+                        self.samples.clear();
+                    },
+                }
+            },
+
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => self.config.scale_factor = *scale_factor,
+
+            _ => {},
+        }
+    }
+
+    // mean velocity of the recent samples in logical pixels per second
+    fn velocity(&self, instant: Instant) -> [f32; 2] {
+
+        let window = self.config.velocity_window;
+        let recent = || self.samples.iter().filter(|(other, _)| instant.saturating_duration_since(*other) <= window);
+
+        let Some(first) = recent().map(|(other, _)| *other).min() else { return [0.0; 2] };
+
+        // the first sample covers the time before it, estimated as one frame at 120hz
+        let span = (instant - first).as_secs_f32() + 1.0 / 120.0;
+
+        let sum = recent().fold([0.0; 2], |[x, y], (_, [dx, dy])| [x + dx, y + dy]);
+
+        [sum[0] / span, sum[1] / span]
+    }
+
+    pub fn has_momentum(&self) -> bool { self.momentum.is_some() }
+
+    pub fn stop(&mut self) { self.momentum = None }
+
+    // adds momentum scrolling up to instant to the frame delta, call once per frame
+    pub fn advance(&mut self, instant: Instant) {
+
+        let Some((velocity, last)) = self.momentum else { return };
+
+        if instant <= last { return }
+
+        let dt = (instant - last).as_secs_f32();
+        let factor = (-self.config.decay * dt).exp();
+        let travelled = if self.config.decay > 0.0 { (1.0 - factor) / self.config.decay } else { dt };
+
+        self.frame[0] += velocity[0] * travelled;
+        self.frame[1] += velocity[1] * travelled;
+
+        let velocity = [velocity[0] * factor, velocity[1] * factor];
+
+        self.momentum = if velocity[0].hypot(velocity[1]) < self.config.min_speed { None } else { Some((velocity, instant)) };
+    }
+
+    pub fn frame_delta(&self) -> [f32; 2] { self.frame }
+
+    pub fn take_frame_delta(&mut self) -> [f32; 2] { std::mem::take(&mut self.frame) }
+}
+
+
+#[cfg(feature = "frame_pacing")]
+impl ScrollState {
+
+    // advances momentum to the current frame time and keeps frames coming while it lasts
+    pub fn frame<U: crate::EventLike>(&mut self, app_ctx: &mut crate::AppCtx<U>) -> [f32; 2] {
+        self.advance(app_ctx.frame_time());
+        if self.has_momentum() { app_ctx.request_frame() }
+        self.take_frame_delta()
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use winit::{event::DeviceId, dpi::PhysicalPosition};
+
+    #[test]
+    fn normalization_and_momentum() {
+
+        let mut scroll = ScrollState::new(ScrollConfig { scale_factor: 2.0, momentum: true, ..Default::default() });
+
+        let wheel = |delta, phase| WindowEvent::MouseWheel { device_id: DeviceId::dummy(), delta, phase };
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        scroll.update_at(&wheel(MouseScrollDelta::LineDelta(0.0, -1.5), TouchPhase::Moved), at(0));
+        assert_eq!(scroll.take_frame_delta(), [0.0, -60.0]);
+
+        scroll.update_at(&wheel(MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.0, 0.0)), TouchPhase::Started), at(0));
+        for i in 1..=10 {
+            scroll.update_at(&wheel(MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.0, 20.0)), TouchPhase::Moved), at(i * 10));
+        }
+        scroll.update_at(&wheel(MouseScrollDelta::PixelDelta(PhysicalPosition::new(0.0, 0.0)), TouchPhase::Ended), at(100));
+
+        assert_eq!(scroll.take_frame_delta(), [0.0, 100.0]);
+        assert!(scroll.has_momentum());
+
+        let mut total = 0.0;
+        for i in 1..=300 {
+            scroll.advance(at(100 + i * 16));
+            total += scroll.take_frame_delta()[1];
+        }
+
+        assert!(!scroll.has_momentum());
+        assert!(total > 100.0 && total < 300.0, "{total}");
+    }
+}
@@ -0,0 +1,272 @@
+use winit::{
+    event::{WindowEvent, KeyEvent, ElementState, MouseButton, MouseScrollDelta, Modifiers},
+    keyboard::{PhysicalKey, KeyCode, Key, ModifiersState},
+    dpi::PhysicalPosition,
+};
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScrollDelta {
+    pub lines: [f32; 2],
+    pub pixels: [f64; 2],
+}
+
+impl ScrollDelta {
+
+    pub fn is_zero(&self) -> bool { self.lines == [0.0; 2] && self.pixels == [0.0; 2] }
+
+    pub fn add(&mut self, delta: MouseScrollDelta) {
+        match delta {
+            MouseScrollDelta::LineDelta(x, y) => { self.lines[0] += x; self.lines[1] += y; },
+            MouseScrollDelta::PixelDelta(pos) => { self.pixels[0] += pos.x; self.pixels[1] += pos.y; },
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct InputState {
+    focused: bool,
+    modifiers: ModifiersState,
+
+    // physical and logical key as it was pressed
+    keys: Vec<(PhysicalKey, Key)>,
+    just_pressed_keys: Vec<(PhysicalKey, Key)>,
+    just_released_keys: Vec<(PhysicalKey, Key)>,
+
+    buttons: Vec<MouseButton>,
+    just_pressed_buttons: Vec<MouseButton>,
+    just_released_buttons: Vec<MouseButton>,
+
+    cursor: Option<PhysicalPosition<f64>>,
+    cursor_delta: [f64; 2],
+    scroll: ScrollDelta,
+    frame_scroll: ScrollDelta,
+}
+
+impl InputState {
+
+    pub fn new() -> Self { Self { focused: true, ..Default::default() } }
+
+    pub fn update(&mut self, event: &WindowEvent) {
+        match event {
+
+            WindowEvent::KeyboardInput { event: KeyEvent { physical_key, logical_key, state, repeat, .. }, .. } => {
+                match state {
+                    ElementState::Pressed if !repeat => self.press_key(*physical_key, logical_key.clone()),
+                    ElementState::Pressed => {},
+                    ElementState::Released => self.release_key(*physical_key),
+                }
+            },
+
+            WindowEvent::ModifiersChanged(modifiers) => self.set_modifiers(*modifiers),
+
+            WindowEvent::MouseInput { state, button, .. } => match state {
+                ElementState::Pressed => self.press_button(*button),
+                ElementState::Released => self.release_button(*button),
+            },
+
+            WindowEvent::CursorMoved { position, .. } => {
+                if let Some(previous) = self.cursor {
+                    self.cursor_delta[0] += position.x - previous.x;
+                    self.cursor_delta[1] += position.y - previous.y;
+                }
+                self.cursor = Some(*position);
+            },
+
+            WindowEvent::CursorLeft { .. } => self.cursor = None,
+
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.scroll.add(*delta);
+                self.frame_scroll.add(*delta);
+            },
+
+            WindowEvent::Focused(focused) => {
+                self.focused = *focused;
+                if !focused { self.release_all() }
+            },
+
+            _ => {},
+        }
+    }
+
+    // clear per-frame state
+    pub fn end_frame(&mut self) {
+        self.just_pressed_keys.clear();
+        self.just_released_keys.clear();
+        self.just_pressed_buttons.clear();
+        self.just_released_buttons.clear();
+        self.cursor_delta = [0.0; 2];
+        self.frame_scroll = ScrollDelta::default();
+    }
+
+    // release everything that is held, e.g. on focus loss when release-events will be missed
+    pub fn release_all(&mut self) {
+        self.just_released_keys.append(&mut self.keys);
+        self.just_released_buttons.append(&mut self.buttons);
+        self.modifiers = ModifiersState::empty();
+    }
+
+
+    // manual updates
+
+    pub fn press_key(&mut self, physical_key: PhysicalKey, logical_key: Key) {
+        if !self.keys.iter().any(|(key, _)| *key == physical_key) {
+            self.keys.push((physical_key, logical_key.clone()));
+            self.just_pressed_keys.push((physical_key, logical_key));
+        }
+    }
+
+    pub fn release_key(&mut self, physical_key: PhysicalKey) {
+        if let Some(i) = self.keys.iter().position(|(key, _)| *key == physical_key) {
+            self.just_released_keys.push(self.keys.remove(i));
+        }
+    }
+
+    pub fn press_button(&mut self, button: MouseButton) {
+        if !self.buttons.contains(&button) {
+            self.buttons.push(button);
+            self.just_pressed_buttons.push(button);
+        }
+    }
+
+    pub fn release_button(&mut self, button: MouseButton) {
+        if let Some(i) = self.buttons.iter().position(|other| *other == button) {
+            self.just_released_buttons.push(self.buttons.remove(i));
+        }
+    }
+
+    pub fn set_modifiers(&mut self, modifiers: Modifiers) {
+        self.modifiers = modifiers.state();
+    }
+
+
+    // state
+
+    pub fn focused(&self) -> bool { self.focused }
+    pub fn modifiers(&self) -> ModifiersState { self.modifiers }
+
+    pub fn key_pressed(&self, code: KeyCode) -> bool { self.physical_key_pressed(PhysicalKey::Code(code)) }
+    pub fn key_just_pressed(&self, code: KeyCode) -> bool { self.physical_key_just_pressed(PhysicalKey::Code(code)) }
+    pub fn key_just_released(&self, code: KeyCode) -> bool { self.physical_key_just_released(PhysicalKey::Code(code)) }
+
+    pub fn physical_key_pressed(&self, key: PhysicalKey) -> bool { self.keys.iter().any(|(other, _)| *other == key) }
+    pub fn physical_key_just_pressed(&self, key: PhysicalKey) -> bool { self.just_pressed_keys.iter().any(|(other, _)| *other == key) }
+    pub fn physical_key_just_released(&self, key: PhysicalKey) -> bool { self.just_released_keys.iter().any(|(other, _)| *other == key) }
+
+    pub fn logical_key_pressed(&self, key: &Key) -> bool { self.keys.iter().any(|(_, other)| other == key) }
+    pub fn logical_key_just_pressed(&self, key: &Key) -> bool { self.just_pressed_keys.iter().any(|(_, other)| other == key) }
+    pub fn logical_key_just_released(&self, key: &Key) -> bool { self.just_released_keys.iter().any(|(_, other)| other == key) }
+
+    pub fn pressed_keys(&self) -> impl Iterator<Item=&(PhysicalKey, Key)> { self.keys.iter() }
+    pub fn just_pressed_keys(&self) -> impl Iterator<Item=&(PhysicalKey, Key)> { self.just_pressed_keys.iter() }
+    pub fn just_released_keys(&self) -> impl Iterator<Item=&(PhysicalKey, Key)> { self.just_released_keys.iter() }
+
+    pub fn mouse_pressed(&self, button: MouseButton) -> bool { self.buttons.contains(&button) }
+    pub fn mouse_just_pressed(&self, button: MouseButton) -> bool { self.just_pressed_buttons.contains(&button) }
+    pub fn mouse_just_released(&self, button: MouseButton) -> bool { self.just_released_buttons.contains(&button) }
+
+    pub fn pressed_buttons(&self) -> &[MouseButton] { &self.buttons }
+
+    pub fn cursor_position(&self) -> Option<PhysicalPosition<f64>> { self.cursor }
+    pub fn cursor_delta(&self) -> [f64; 2] { self.cursor_delta }
+
+    // accumulated since the last take_scroll / in the current frame
+    pub fn scroll(&self) -> ScrollDelta { self.scroll }
+    pub fn take_scroll(&mut self) -> ScrollDelta { std::mem::take(&mut self.scroll) }
+    pub fn frame_scroll(&self) -> ScrollDelta { self.frame_scroll }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use winit::event::{DeviceId, TouchPhase};
+
+    const DEVICE_ID: DeviceId = DeviceId::dummy();
+
+    #[test]
+    fn mouse_buttons_and_cursor() {
+
+        let mut input = InputState::new();
+
+        input.update(&WindowEvent::CursorMoved { device_id: DEVICE_ID, position: PhysicalPosition::new(10.0, 20.0) });
+        input.update(&WindowEvent::CursorMoved { device_id: DEVICE_ID, position: PhysicalPosition::new(15.0, 18.0) });
+        input.update(&WindowEvent::MouseInput { device_id: DEVICE_ID, state: ElementState::Pressed, button: MouseButton::Left });
+
+        assert_eq!(input.cursor_position(), Some(PhysicalPosition::new(15.0, 18.0)));
+        assert_eq!(input.cursor_delta(), [5.0, -2.0]);
+        assert!(input.mouse_pressed(MouseButton::Left) && input.mouse_just_pressed(MouseButton::Left));
+
+        input.end_frame();
+
+        assert!(input.mouse_pressed(MouseButton::Left) && !input.mouse_just_pressed(MouseButton::Left));
+        assert_eq!(input.cursor_delta(), [0.0, 0.0]);
+
+        input.update(&WindowEvent::MouseInput { device_id: DEVICE_ID, state: ElementState::Released, button: MouseButton::Left });
+        input.update(&WindowEvent::CursorLeft { device_id: DEVICE_ID });
+
+        assert!(!input.mouse_pressed(MouseButton::Left) && input.mouse_just_released(MouseButton::Left));
+        assert_eq!(input.cursor_position(), None);
+    }
+
+    #[test]
+    fn keys_keep_logical_key_of_press() {
+
+        let mut input = InputState::new();
+
+        input.press_key(PhysicalKey::Code(KeyCode::KeyA), Key::Character("A".into()));
+        input.press_key(PhysicalKey::Code(KeyCode::KeyA), Key::Character("a".into())); // ignored, still held
+
+        assert!(input.key_pressed(KeyCode::KeyA));
+        assert!(input.logical_key_just_pressed(&Key::Character("A".into())));
+        assert_eq!(input.pressed_keys().count(), 1);
+
+        input.end_frame();
+        input.release_key(PhysicalKey::Code(KeyCode::KeyA));
+
+        assert!(!input.key_pressed(KeyCode::KeyA));
+        assert!(input.key_just_released(KeyCode::KeyA));
+        assert!(input.logical_key_just_released(&Key::Character("A".into())));
+    }
+
+    #[test]
+    fn focus_loss_releases_everything() {
+
+        let mut input = InputState::new();
+
+        input.press_key(PhysicalKey::Code(KeyCode::ShiftLeft), Key::Named(winit::keyboard::NamedKey::Shift));
+        input.update(&WindowEvent::ModifiersChanged(ModifiersState::SHIFT.into()));
+        input.update(&WindowEvent::MouseInput { device_id: DEVICE_ID, state: ElementState::Pressed, button: MouseButton::Right });
+        input.end_frame();
+
+        input.update(&WindowEvent::Focused(false));
+
+        assert!(!input.focused());
+        assert!(!input.key_pressed(KeyCode::ShiftLeft) && input.key_just_released(KeyCode::ShiftLeft));
+        assert!(!input.mouse_pressed(MouseButton::Right) && input.mouse_just_released(MouseButton::Right));
+        assert_eq!(input.modifiers(), ModifiersState::empty());
+    }
+
+    #[test]
+    fn scroll_accumulation() {
+
+        let mut input = InputState::new();
+
+        let wheel = |delta| WindowEvent::MouseWheel { device_id: DEVICE_ID, delta, phase: TouchPhase::Moved };
+
+        input.update(&wheel(MouseScrollDelta::LineDelta(0.0, 1.0)));
+        input.update(&wheel(MouseScrollDelta::LineDelta(0.5, 2.0)));
+        input.update(&wheel(MouseScrollDelta::PixelDelta(PhysicalPosition::new(3.0, 4.0))));
+
+        assert_eq!(input.frame_scroll(), ScrollDelta { lines: [0.5, 3.0], pixels: [3.0, 4.0] });
+
+        input.end_frame();
+        assert!(input.frame_scroll().is_zero());
+
+        assert_eq!(input.take_scroll(), ScrollDelta { lines: [0.5, 3.0], pixels: [3.0, 4.0] });
+        assert!(input.scroll().is_zero());
+    }
+}
@@ -40,6 +40,11 @@ pub mod touches;
 
 pub mod timer;
 
+pub mod tween;
+
+#[cfg(feature="input")]
+pub mod input;
+
 #[cfg(feature="storage")]
 pub mod storage;
 
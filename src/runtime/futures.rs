@@ -122,12 +122,19 @@ mod async_timeout {
 
   pub struct AsyncTimeout<T: IdLike + From<AsyncTimeoutId>> {
     instant: Instant,
+    leeway: Duration,
     timer: Option<RuntimeTimer<T>>,
   }
 
   impl<T: IdLike + From<AsyncTimeoutId>> AsyncTimeout<T> {
     pub fn new(timer: RuntimeTimer<T>, instant: Instant) -> Self {
-      Self {timer: Some(timer), instant}
+      Self {timer: Some(timer), instant, leeway: Duration::ZERO}
+    }
+
+    // see Timer::set_timeout_leeway
+    pub fn with_leeway(mut self, leeway: Duration) -> Self {
+      self.leeway = leeway;
+      self
     }
   }
 
@@ -142,7 +149,7 @@ mod async_timeout {
 
       if let Some(timer) = self.timer.take() {
         let id = AsyncTimeoutId(context.waker().clone());
-        timer.borrow_mut().set_timeout(T::from(id), self.instant);
+        timer.borrow_mut().set_timeout_leeway(T::from(id), self.instant, self.leeway);
       }
 
       Poll::Pending
@@ -166,6 +173,14 @@ mod async_timeout {
       Instant::now().checked_add(duraion).map(|instant| self.timeout(instant))
     }
 
+    pub fn timeout_leeway(&self, instant: Instant, leeway: Duration) -> AsyncTimeout<T> {
+      self.timeout(instant).with_leeway(leeway)
+    }
+
+    pub fn wait_leeway(&self, duraion: Duration, leeway: Duration) -> Option<AsyncTimeout<T>> {
+      self.wait(duraion).map(|timeout| timeout.with_leeway(leeway))
+    }
+
   }
 
 }
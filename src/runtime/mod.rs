@@ -15,7 +15,7 @@ mod timer;
 
 #[cfg(any(feature="timeout", feature="async_timeout", feature="frame_pacing"))]
 pub use self::{
-  timer::{RuntimeTimer, Timer, TimeoutResult},
+  timer::{RuntimeTimer, Timer, TimeoutResult, WakeStats, ClockChange},
   futures::{AsyncTimeoutId, AsyncTimeout, AsyncTimer},
 };
 
@@ -98,6 +98,10 @@ pub enum RuntimeEvent<F: Futures, U: EventLike, T: IdLike> {
   #[cfg(any(feature="timeout", feature="async_timeout", feature="frame_pacing"))]
   Timeout {id: T, instant: time::Instant},
 
+  // e.g. resumed from system sleep, wall-clock timeouts are rescheduled already
+  #[cfg(any(feature="timeout", feature="async_timeout", feature="frame_pacing"))]
+  ClockChanged(ClockChange),
+
   #[cfg(not(any(feature="timeout", feature="async_timeout", feature="frame_pacing")))]
   Timeout((!, std::marker::PhantomData<T>)),
 }
@@ -155,14 +159,42 @@ impl<R: Runtime> RuntimeMount<R> {
     Self::new(event_loop.create_proxy(), runtime).run(event_loop);
   }
 
+  // see Timer::set_precision
+  #[cfg(any(feature="timeout", feature="async_timeout", feature="frame_pacing"))]
+  pub fn with_precision(self, slack: Option<time::Duration>) -> Self {
+    self.ctx.timer.borrow_mut().set_precision(slack);
+    self
+  }
+
   pub fn event(&mut self, event_loop: &ActiveEventLoop, event: RuntimeEvent<R::Futures, R::UserEvent, R::TimeoutId>) {
 
     self.runtime.event(event_loop, &mut self.ctx, event);
 
     #[cfg(any(feature="timeout", feature="async_timeout", feature="frame_pacing"))]
-    if let Some(set_instant) = self.ctx.timer.borrow_mut().take_set_instant() {
+    self.update_control_flow(event_loop);
+  }
+
+  #[cfg(any(feature="timeout", feature="async_timeout", feature="frame_pacing"))]
+  fn resume_time_reached(&mut self, event_loop: &ActiveEventLoop) {
+
+    #[cfg(not(target_family="wasm"))]
+    self.ctx.timer.borrow().spin_until_due();
+
+    let now = time::Instant::now();
+    self.ctx.timer.borrow_mut().record_wake(now);
+
+    // all timeouts due by now, coalesced into this wake-up
+    while let Some(timer::Timeout {id, instant, ..}) = { self.ctx.timer.borrow_mut().pop_timeout(now) } {
+      self.event(event_loop, RuntimeEvent::Timeout {id, instant});
+    }
+  }
+
+  #[cfg(any(feature="timeout", feature="async_timeout", feature="frame_pacing"))]
+  fn update_control_flow(&self, event_loop: &ActiveEventLoop) {
+    let mut timer = self.ctx.timer.borrow_mut();
+    if let Some(set_instant) = timer.take_set_instant() {
       match set_instant {
-        Some(instant) => event_loop.set_wait_until(instant),
+        Some(instant) => event_loop.set_wait_until(timer.wait_instant(instant)),
         None => event_loop.set_wait(),
       }
     }
@@ -174,11 +206,17 @@ impl<R: Runtime> ApplicationHandler<RuntimeEventExt<R::FutureId, R::UserEvent>>
 
   #[cfg(any(feature="timeout", feature="async_timeout", feature="frame_pacing"))]
   fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
-    if matches!(cause, StartCause::ResumeTimeReached {..}) &&
-      let Some(timer::Timeout {id, instant}) = { self.ctx.timer.borrow_mut().pop_timeout() }
-    {
-      self.event(event_loop, RuntimeEvent::Timeout {id, instant});
+    let timer_wake = matches!(cause, StartCause::ResumeTimeReached {..});
+
+    let clock_change = { self.ctx.timer.borrow_mut().check_clock(timer_wake) };
+    if let Some(change) = clock_change {
+      self.event(event_loop, RuntimeEvent::ClockChanged(change));
     }
+
+    if timer_wake { self.resume_time_reached(event_loop) }
+
+    // e.g. a periodic clock check without any due timeouts
+    self.update_control_flow(event_loop);
   }
 
   fn resumed(&mut self, event_loop: &ActiveEventLoop) {
@@ -1,6 +1,6 @@
 
 use std::{rc::Rc, cell::RefCell, collections::VecDeque};
-use crate::{time::{Instant, Duration}, shrink_capacity};
+use crate::{time::{Instant, Duration, SystemTime}, shrink_capacity};
 
 use super::IdLike;
 
@@ -23,26 +23,122 @@ impl TimeoutResult {
 pub(super) struct Timeout<Id: IdLike> {
   pub id: Id,
   pub instant: Instant,
+  pub leeway: Duration, // may fire up to this late, to be coalesced with other timeouts
+  pub wall: Option<SystemTime>, // wall-clock timeouts are rescheduled on clock changes
+}
+
+impl<Id: IdLike> Timeout<Id> {
+  fn deadline(&self) -> Instant { self.instant.checked_add(self.leeway).unwrap_or(self.instant) }
 }
 
 
 pub type RuntimeTimer<T> = Rc<RefCell<Timer<T>>>;
 
 
+// lateness of timer wake-ups, measured against the requested wake-up instants
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WakeStats {
+  pub wakes: u64,
+  pub last: Duration,
+  pub max: Duration,
+  pub total: Duration,
+}
+
+impl WakeStats {
+
+  pub fn avg(&self) -> Option<Duration> {
+    (self.wakes > 0).then(|| self.total.div_f64(self.wakes as f64))
+  }
+
+  fn add(&mut self, lateness: Duration) {
+    self.wakes += 1;
+    self.last = lateness;
+    self.max = self.max.max(lateness);
+    self.total += lateness;
+  }
+}
+
+
+// detected difference between monotonic and wall time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClockChange {
+  Forward(Duration), // e.g. resumed from system sleep, or the system clock was set forward
+  Backward(Duration),
+}
+
+
 #[derive(Debug, Clone)]
 pub struct Timer<Id: IdLike> {
   queue: VecDeque<Timeout<Id>>,
   set_instant: Option<Option<Instant>>,
+  wake: Option<Instant>,
+  precision: Option<Duration>,
+  wake_stats: WakeStats,
+  clock_ref: (Instant, SystemTime),
+  pub clock_jump_threshold: Duration,
+  pub wall_check_interval: Duration, // while wall-clock timeouts are pending
 }
 
 impl<Id: IdLike> Timer<Id> {
 
   pub(super) fn new() -> Self {
-    Self { queue: Default::default(), set_instant: None }
+    Self {
+      queue: Default::default(), set_instant: None, wake: None, precision: None, wake_stats: WakeStats::default(),
+      clock_ref: (Instant::now(), SystemTime::now()),
+      clock_jump_threshold: Duration::from_secs(2), wall_check_interval: Duration::from_secs(30),
+    }
+  }
+
+  pub fn precision(&self) -> Option<Duration> { self.precision }
+
+  // precision mode, wakes up earlier by the slack and spin-yields until the exact instant
+  // not available on the web, where the event loop cannot be blocked
+  pub fn set_precision(&mut self, slack: Option<Duration>) {
+    self.precision = slack.filter(|slack| !slack.is_zero());
+    self.set_instant = Some(self.wake);
+  }
+
+  pub fn wake_stats(&self) -> &WakeStats { &self.wake_stats }
+
+  pub fn reset_wake_stats(&mut self) { self.wake_stats = WakeStats::default() }
+
+  // instant to wait for in the event loop
+  pub(super) fn wait_instant(&self, instant: Instant) -> Instant {
+    if cfg!(target_family = "wasm") { return instant }
+    self.precision.and_then(|slack| instant.checked_sub(slack)).unwrap_or(instant)
+  }
+
+  // spins if the earliest timeout is due within the slack
+  #[cfg(not(target_family = "wasm"))]
+  pub(super) fn spin_until_due(&self) {
+    if let (Some(slack), Some(instant)) = (self.precision, self.wake)
+      && instant.saturating_duration_since(Instant::now()) <= slack
+    {
+      while Instant::now() < instant { std::thread::yield_now() }
+    }
   }
 
-  pub(super) fn earliest_instant(&self) -> Option<Instant> {
-    self.queue.front().map(|t| t.instant)
+  pub(super) fn record_wake(&mut self, now: Instant) {
+    if let Some(wake) = self.wake && wake <= now {
+      self.wake_stats.add(now - wake);
+    }
+  }
+
+  // the earliest deadline, all timeouts due by then are handled with a single wake-up
+  // pending wall-clock timeouts additionally require periodic clock checks
+  fn wake_instant(&self) -> Option<Instant> {
+    let check = self.queue.iter().any(|t| t.wall.is_some())
+      .then(|| self.clock_ref.0.checked_add(self.wall_check_interval)).flatten();
+
+    self.queue.iter().map(Timeout::deadline).chain(check).min()
+  }
+
+  fn update_wake(&mut self) {
+    let wake = self.wake_instant();
+    if wake != self.wake {
+      self.wake = wake;
+      self.set_instant = Some(wake);
+    }
   }
 
   pub(super) fn take_set_instant(&mut self) -> Option<Option<Instant>> {
@@ -66,7 +162,7 @@ impl<Id: IdLike> Timer<Id> {
       }
 
       self.queue.remove(i);
-      if i == 0 { self.set_instant = Some(self.earliest_instant()); }
+      self.update_wake();
 
       TimeoutResult::Canceled(instant)
     }
@@ -83,31 +179,48 @@ impl<Id: IdLike> Timer<Id> {
       return timeout_result;
     }
 
-    if self.queue.is_empty() {
-      self.set_instant = Some(Some(timeout.instant));
-      self.queue.push_back(timeout);
-    }
-    else if let Some(i) = self.queue.iter().position(|t| timeout.instant < t.instant) {
-      if i == 0 { self.set_instant = Some(Some(timeout.instant)); }
+    if let Some(i) = self.queue.iter().position(|t| timeout.instant < t.instant) {
       self.queue.insert(i, timeout);
     } else {
       self.queue.push_back(timeout);
     }
 
+    self.update_wake();
+
     timeout_result
   }
 
+  fn wall_instant(time: SystemTime) -> Option<Instant> {
+    match time.duration_since(SystemTime::now()) {
+      Ok(wait) => Instant::now().checked_add(wait),
+      Err(_) => Some(Instant::now()),
+    }
+  }
+
   pub fn set_timeout(&mut self, id: Id, instant: Instant) -> TimeoutResult {
-    self.set_timeout_opt_earlier(Timeout {id, instant}, false)
+    self.set_timeout_opt_earlier(Timeout {id, instant, leeway: Duration::ZERO, wall: None}, false)
   }
 
   pub fn set_timeout_earlier(&mut self, id: Id, instant: Instant) -> TimeoutResult {
-    self.set_timeout_opt_earlier(Timeout {id, instant}, true)
+    self.set_timeout_opt_earlier(Timeout {id, instant, leeway: Duration::ZERO, wall: None}, true)
   }
 
   pub fn set_timeout_wait(&mut self, id: Id, duraion: Duration) -> TimeoutResult {
     if let Some(instant) = Instant::now().checked_add(duraion) {
-      self.set_timeout_opt_earlier(Timeout {id, instant}, false)
+      self.set_timeout_opt_earlier(Timeout {id, instant, leeway: Duration::ZERO, wall: None}, false)
+    } else {
+      self.cancel_timeout(&id, None)
+    }
+  }
+
+  // coalesced with other timeouts, fires between instant and instant + leeway
+  pub fn set_timeout_leeway(&mut self, id: Id, instant: Instant, leeway: Duration) -> TimeoutResult {
+    self.set_timeout_opt_earlier(Timeout {id, instant, leeway, wall: None}, false)
+  }
+
+  pub fn set_timeout_wait_leeway(&mut self, id: Id, duraion: Duration, leeway: Duration) -> TimeoutResult {
+    if let Some(instant) = Instant::now().checked_add(duraion) {
+      self.set_timeout_opt_earlier(Timeout {id, instant, leeway, wall: None}, false)
     } else {
       self.cancel_timeout(&id, None)
     }
@@ -115,7 +228,7 @@ impl<Id: IdLike> Timer<Id> {
 
   pub fn set_timeout_wait_earlier(&mut self, id: Id, duraion: Duration) -> TimeoutResult {
     if let Some(instant) = Instant::now().checked_add(duraion) {
-      self.set_timeout_opt_earlier(Timeout {id, instant}, true)
+      self.set_timeout_opt_earlier(Timeout {id, instant, leeway: Duration::ZERO, wall: None}, true)
     }
     else if let Some(instant) = self.get_timeout(&id) {
       // MAX is always later than any instant
@@ -127,11 +240,62 @@ impl<Id: IdLike> Timer<Id> {
   }
 
 
-  pub(super) fn pop_timeout(&mut self) -> Option<Timeout<Id>> {
+  // wall-clock timeouts, rescheduled on detected clock changes, e.g. after system sleep
+
+  pub fn get_wall_timeout(&self, id: &Id) -> Option<SystemTime> {
+    self.queue.iter().find_map(|t| (id == &t.id).then_some(t.wall).flatten())
+  }
+
+  pub fn set_wall_timeout(&mut self, id: Id, time: SystemTime) -> TimeoutResult {
+    self.set_wall_timeout_leeway(id, time, Duration::ZERO)
+  }
+
+  pub fn set_wall_timeout_leeway(&mut self, id: Id, time: SystemTime, leeway: Duration) -> TimeoutResult {
+    if let Some(instant) = Self::wall_instant(time) {
+      self.set_timeout_opt_earlier(Timeout {id, instant, leeway, wall: Some(time)}, false)
+    } else {
+      self.cancel_timeout(&id, None)
+    }
+  }
+
+  // compares the elapsed monotonic and wall time since the last check
+  // unless forced, e.g. on timer wake-ups, checks at most once per second
+  pub(super) fn check_clock(&mut self, force: bool) -> Option<ClockChange> {
+
+    let instant = Instant::now();
+    if !force && instant.saturating_duration_since(self.clock_ref.0) < Duration::from_secs(1) { return None }
+
+    let system_time = SystemTime::now();
+    let (last_instant, last_system_time) = std::mem::replace(&mut self.clock_ref, (instant, system_time));
+
+    let monotonic = instant.saturating_duration_since(last_instant);
+    let threshold = self.clock_jump_threshold;
+
+    let change = match system_time.duration_since(last_system_time) {
+      Ok(wall) if wall > monotonic + threshold => Some(ClockChange::Forward(wall - monotonic)),
+      Ok(wall) if wall + threshold < monotonic => Some(ClockChange::Backward(monotonic - wall)),
+      Err(err) if err.duration() + monotonic > threshold => Some(ClockChange::Backward(err.duration() + monotonic)),
+      _ => None,
+    };
+
+    if change.is_some() {
+      for timeout in self.queue.iter_mut() {
+        if let Some(instant) = timeout.wall.and_then(Self::wall_instant) { timeout.instant = instant }
+      }
+      self.queue.make_contiguous().sort_by_key(|t| t.instant);
+    }
+
+    // moves the next periodic check as well
+    self.update_wake();
+
+    change
+  }
+
+  pub(super) fn pop_timeout(&mut self, now: Instant) -> Option<Timeout<Id>> {
 
-    if let Some(timeout) = self.queue.front() && timeout.instant <= Instant::now() {
+    if let Some(timeout) = self.queue.front() && timeout.instant <= now {
       let popped = self.queue.pop_front();
-      self.set_instant = Some(self.earliest_instant());
+      self.update_wake();
       shrink_capacity!(self.queue, 256);
       return popped;
     }
@@ -139,4 +303,108 @@ impl<Id: IdLike> Timer<Id> {
     None
   }
 
-}
\ No newline at end of file
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+  use super::*;
+
+  #[test]
+  fn coalesce_with_leeway() {
+
+    let mut timer = Timer::<u32>::new();
+    let start = Instant::now() + Duration::from_secs(1);
+    let at = |ms| start + Duration::from_millis(ms);
+
+    timer.set_timeout_leeway(1, at(0), Duration::from_millis(50));
+    timer.set_timeout_leeway(2, at(30), Duration::from_millis(100));
+    timer.set_timeout(3, at(200));
+
+    // woken once at the first deadline, with both overlapping timeouts due
+    assert_eq!(timer.take_set_instant(), Some(Some(at(50))));
+    assert_eq!(timer.pop_timeout(at(50)).map(|t| t.id), Some(1));
+    assert_eq!(timer.pop_timeout(at(50)).map(|t| t.id), Some(2));
+    assert_eq!(timer.pop_timeout(at(50)), None);
+
+    // without leeway the instant is exact
+    assert_eq!(timer.take_set_instant(), Some(Some(at(200))));
+  }
+
+  #[test]
+  fn wall_timeout_checks_clock() {
+
+    let mut timer = Timer::<u32>::new();
+    let time = SystemTime::now() + Duration::from_secs(3600);
+
+    timer.set_wall_timeout(1, time);
+    assert_eq!(timer.get_wall_timeout(&1), Some(time));
+
+    // woken for periodic clock checks until due
+    assert_eq!(timer.take_set_instant(), Some(Some(timer.clock_ref.0 + timer.wall_check_interval)));
+
+    timer.set_timeout(1, Instant::now());
+    assert_eq!(timer.get_wall_timeout(&1), None);
+  }
+
+  #[test]
+  fn clock_jump_reschedules_wall_timeouts() {
+
+    let mut timer = Timer::<u32>::new();
+    let (instant, system_time) = (Instant::now(), SystemTime::now());
+
+    timer.set_timeout(2, instant + Duration::from_secs(3600));
+    timer.set_wall_timeout(1, system_time + Duration::from_secs(600));
+
+    // as if the monotonic clock stood still during an hour of system sleep
+    timer.queue[0].instant = instant + Duration::from_secs(3 * 3600);
+    timer.queue.make_contiguous().sort_by_key(|t| t.instant);
+    timer.clock_ref = (Instant::now(), SystemTime::now() - Duration::from_secs(3600));
+    timer.take_set_instant();
+
+    assert!(matches!(timer.check_clock(true), Some(ClockChange::Forward(jump)) if jump > Duration::from_secs(3590)));
+
+    let rescheduled = timer.queue[0];
+    assert_eq!(rescheduled.id, 1);
+    assert!(rescheduled.instant >= instant + Duration::from_secs(600) && rescheduled.instant < Instant::now() + Duration::from_secs(600));
+    assert_eq!(timer.queue[1].id, 2);
+
+    // the next periodic check comes first
+    assert_eq!(timer.take_set_instant(), Some(Some(timer.clock_ref.0 + timer.wall_check_interval)));
+
+    // checks are throttled unless forced
+    timer.clock_ref.1 -= Duration::from_secs(3600);
+    assert_eq!(timer.check_clock(false), None);
+    assert!(matches!(timer.check_clock(true), Some(ClockChange::Forward(_))));
+  }
+
+  #[test]
+  fn precision_and_wake_stats() {
+
+    let mut timer = Timer::<u32>::new();
+    let instant = Instant::now() + Duration::from_secs(1);
+    let ms = Duration::from_millis;
+
+    timer.set_precision(Some(Duration::ZERO));
+    assert_eq!(timer.precision(), None);
+    assert_eq!(timer.wait_instant(instant), instant);
+
+    timer.set_precision(Some(ms(2)));
+    assert_eq!(timer.wait_instant(instant), instant - ms(2));
+
+    // lateness against the requested wake-up, early wake-ups are not recorded
+    timer.set_timeout(1, instant);
+    timer.record_wake(instant - ms(1));
+    timer.record_wake(instant + ms(1));
+    timer.record_wake(instant + ms(3));
+
+    let stats = timer.wake_stats();
+    assert_eq!((stats.wakes, stats.last, stats.max), (2, ms(3), ms(3)));
+    assert_eq!(stats.avg(), Some(ms(2)));
+
+    timer.reset_wake_stats();
+    assert_eq!(timer.wake_stats().avg(), None);
+  }
+}
@@ -12,7 +12,16 @@ pub struct NormInterval {
 impl NormInterval {
 
     pub fn new(duration: Duration) -> Self {
-        Self { instant: Instant::now(), duration }
+        Self::new_at(duration, Instant::now())
+    }
+
+    pub fn new_at(duration: Duration, instant: Instant) -> Self {
+        Self { instant, duration }
+    }
+
+    // to be queried with app instants, e.g. AppCtx::app_now
+    pub fn with_clock(duration: Duration, clock: &AppClock) -> Self {
+        Self::new_at(duration, clock.now())
     }
 
     pub fn from_secs(duration_secs: f64) -> Self {
@@ -57,7 +66,16 @@ pub struct StepInterval {
 impl StepInterval {
 
     pub fn new(duration: Duration) -> Self {
-        Self { next: Instant::now() + duration, duration }
+        Self::new_at(duration, Instant::now())
+    }
+
+    pub fn new_at(duration: Duration, instant: Instant) -> Self {
+        Self { next: instant + duration, duration }
+    }
+
+    // to be queried with app instants, e.g. AppCtx::app_now
+    pub fn with_clock(duration: Duration, clock: &AppClock) -> Self {
+        Self::new_at(duration, clock.now())
     }
 
     pub fn from_secs(duration_secs: f64) -> Self {
@@ -138,4 +156,340 @@ impl IntervalCounter {
         }
         else { None }
     }
-}
\ No newline at end of file
+}
+
+
+// statistics over the most recent frame intervals
+
+pub const FRAME_STATS_CAPACITY: usize = 240;
+
+#[derive(Debug, Clone)]
+pub struct FrameStats {
+    intervals: std::collections::VecDeque<Duration>,
+    last: Option<Instant>,
+    pub frames: u64,
+    pub missed: u64, // vsync intervals without a frame during continuous rendering
+    pub smoothing: f64, // weight of the newest interval for the smoothed fps
+    smoothed_fps: Option<f64>,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self { Self::new() }
+}
+
+impl FrameStats {
+
+    pub fn new() -> Self {
+        Self {
+            intervals: std::collections::VecDeque::with_capacity(FRAME_STATS_CAPACITY),
+            last: None, frames: 0, missed: 0, smoothing: 0.1, smoothed_fps: None,
+        }
+    }
+
+    // continuous: the frame was requested while rendering the previous one,
+    // otherwise the time since then was idle and is not recorded as an interval
+    pub fn add_frame(&mut self, instant: Instant, frame_duration: Duration, continuous: bool) {
+
+        self.frames += 1;
+
+        if let Some(last) = self.last.replace(instant) && continuous && instant > last {
+
+            let interval = instant - last;
+
+            if self.intervals.len() == FRAME_STATS_CAPACITY { self.intervals.pop_front(); }
+            self.intervals.push_back(interval);
+
+            if !frame_duration.is_zero() {
+                let vsyncs = (interval.as_secs_f64() / frame_duration.as_secs_f64()).round() as u64;
+                self.missed += vsyncs.saturating_sub(1);
+            }
+
+            let fps = 1.0 / interval.as_secs_f64();
+            self.smoothed_fps = Some(match self.smoothed_fps {
+                Some(smoothed) => smoothed + (fps - smoothed) * self.smoothing,
+                None => fps,
+            });
+        }
+    }
+
+    pub fn reset(&mut self) { *self = Self { smoothing: self.smoothing, ..Self::new() } }
+
+    pub fn intervals(&self) -> impl Iterator<Item=Duration> + '_ { self.intervals.iter().copied() }
+
+    pub fn last(&self) -> Option<Duration> { self.intervals.back().copied() }
+    pub fn min(&self) -> Option<Duration> { self.intervals().min() }
+    pub fn max(&self) -> Option<Duration> { self.intervals().max() }
+
+    pub fn avg(&self) -> Option<Duration> {
+        (!self.intervals.is_empty()).then(|| self.intervals().sum::<Duration>() / self.intervals.len() as u32)
+    }
+
+    // nearest-rank percentile, p in 0..=100
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        if self.intervals.is_empty() { return None }
+        let mut sorted: Vec<_> = self.intervals().collect();
+        sorted.sort_unstable();
+        let rank = (p.clamp(0.0, 100.0) / 100.0 * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.saturating_sub(1)])
+    }
+
+    // counts of intervals per bucket of bucket_size, the last bucket collects all longer ones
+    pub fn histogram(&self, bucket_size: Duration, buckets: usize) -> Vec<usize> {
+        let mut histogram = vec![0; buckets];
+        if buckets == 0 || bucket_size.is_zero() { return histogram }
+        for interval in self.intervals() {
+            let i = (interval.as_nanos() / bucket_size.as_nanos()) as usize;
+            histogram[i.min(buckets - 1)] += 1;
+        }
+        histogram
+    }
+
+    pub fn smoothed_fps(&self) -> Option<f64> { self.smoothed_fps }
+}
+
+
+
+// fixed-update / variable-render loop
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedSteps {
+    pub steps: u32, // fixed updates to run this frame
+    pub alpha: f64, // 0..1 between the previous and the current fixed state, for rendering
+}
+
+#[derive(Debug, Clone)]
+pub struct FixedStep {
+    pub step: Duration,
+    pub max_steps: u32, // per frame, excess time is dropped to avoid a spiral of death
+    pub dropped: u64, // dropped steps in total
+    accumulator: Duration,
+    last: Option<Instant>,
+}
+
+impl FixedStep {
+
+    pub fn new(step: Duration) -> Self {
+        Self { step, max_steps: 8, dropped: 0, accumulator: Duration::ZERO, last: None }
+    }
+
+    // None for rates that are not positive and finite
+    pub fn from_hz(hz: f64) -> Option<Self> {
+        Duration::try_from_secs_f64(1.0 / hz).ok().filter(|step| !step.is_zero()).map(Self::new)
+    }
+
+    pub fn with_max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    // the first call only starts the clock
+    pub fn advance(&mut self, frame_time: Instant) -> FixedSteps {
+
+        if let Some(last) = self.last.replace(frame_time) {
+            self.accumulator += frame_time.saturating_duration_since(last);
+        }
+
+        if self.step.is_zero() { return FixedSteps { steps: 0, alpha: 0.0 } }
+
+        let elapsed = self.accumulator.as_nanos() / self.step.as_nanos();
+        self.accumulator = Duration::from_nanos((self.accumulator.as_nanos() % self.step.as_nanos()) as u64);
+
+        let steps = elapsed.min(self.max_steps as u128) as u32;
+        self.dropped += (elapsed - steps as u128) as u64;
+
+        FixedSteps { steps, alpha: self.alpha() }
+    }
+
+    pub fn alpha(&self) -> f64 {
+        if self.step.is_zero() { 0.0 } else { self.accumulator.as_secs_f64() / self.step.as_secs_f64() }
+    }
+
+    // e.g. after a pause, so that the paused time is not caught up
+    pub fn reset(&mut self) {
+        self.accumulator = Duration::ZERO;
+        self.last = None;
+    }
+}
+
+#[cfg(feature = "frame_pacing")]
+impl FixedStep {
+    pub fn advance_frame<U: crate::EventLike>(&mut self, app_ctx: &crate::AppCtx<U>) -> FixedSteps {
+        self.advance(app_ctx.frame_time())
+    }
+}
+
+
+
+// pausable and scalable app time, separate from wall time
+// app instants (origin + elapsed app time) can drive NormInterval, StepInterval, tweens etc.
+
+#[derive(Debug, Clone, Copy)]
+pub struct AppClock {
+    origin: Instant, // app time zero
+    anchor: Instant, // wall time of the last pause, resume or scale change
+    anchor_elapsed: Duration, // app time at the anchor
+    scale: f64,
+    paused: bool,
+}
+
+impl Default for AppClock {
+    fn default() -> Self { Self::new() }
+}
+
+impl AppClock {
+
+    pub fn new() -> Self { Self::new_at(Instant::now()) }
+
+    pub fn new_at(instant: Instant) -> Self {
+        Self { origin: instant, anchor: instant, anchor_elapsed: Duration::ZERO, scale: 1.0, paused: false }
+    }
+
+    pub fn is_paused(&self) -> bool { self.paused }
+
+    pub fn scale(&self) -> f64 { self.scale }
+
+    // app time at the wall time instant, instants before the last change are clamped to it
+    pub fn elapsed_at(&self, instant: Instant) -> Duration {
+        if self.paused { return self.anchor_elapsed }
+        self.anchor_elapsed + instant.saturating_duration_since(self.anchor).mul_f64(self.scale)
+    }
+
+    pub fn elapsed(&self) -> Duration { self.elapsed_at(Instant::now()) }
+
+    pub fn instant_at(&self, instant: Instant) -> Instant { self.origin + self.elapsed_at(instant) }
+
+    pub fn now(&self) -> Instant { self.instant_at(Instant::now()) }
+
+    // wall time at which the app time will be reached, None while paused or stopped
+    pub fn wall_instant(&self, elapsed: Duration, now: Instant) -> Option<Instant> {
+        let current = self.elapsed_at(now);
+        if elapsed <= current { return Some(now) }
+        if self.paused || self.scale <= 0.0 { return None }
+        now.checked_add((elapsed - current).div_f64(self.scale))
+    }
+
+    fn set_anchor(&mut self, instant: Instant) {
+        self.anchor_elapsed = self.elapsed_at(instant);
+        self.anchor = instant;
+    }
+
+    pub fn pause_at(&mut self, instant: Instant) {
+        if !self.paused {
+            self.set_anchor(instant);
+            self.paused = true;
+        }
+    }
+
+    pub fn resume_at(&mut self, instant: Instant) {
+        if self.paused {
+            self.anchor = instant;
+            self.paused = false;
+        }
+    }
+
+    pub fn set_paused_at(&mut self, paused: bool, instant: Instant) {
+        if paused { self.pause_at(instant) } else { self.resume_at(instant) }
+    }
+
+    // negative scales are clamped to 0, the clock does not run backwards
+    pub fn set_scale_at(&mut self, scale: f64, instant: Instant) {
+        self.set_anchor(instant);
+        self.scale = scale.max(0.0);
+    }
+
+    pub fn pause(&mut self) { self.pause_at(Instant::now()) }
+    pub fn resume(&mut self) { self.resume_at(Instant::now()) }
+    pub fn set_scale(&mut self, scale: f64) { self.set_scale_at(scale, Instant::now()) }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn frame_stats() {
+
+        let mut stats = FrameStats::new();
+        let frame = Duration::from_millis(10);
+        let mut instant = Instant::now();
+
+        stats.add_frame(instant, frame, false);
+
+        for ms in [10, 10, 20, 10, 30] {
+            instant += Duration::from_millis(ms);
+            stats.add_frame(instant, frame, true);
+        }
+
+        // idle gap
+        instant += Duration::from_secs(1);
+        stats.add_frame(instant, frame, false);
+
+        assert_eq!(stats.frames, 7);
+        assert_eq!(stats.missed, 3);
+        assert_eq!(stats.min(), Some(frame));
+        assert_eq!(stats.max(), Some(Duration::from_millis(30)));
+        assert_eq!(stats.avg(), Some(Duration::from_millis(16)));
+        assert_eq!(stats.percentile(50.0), Some(frame));
+        assert_eq!(stats.percentile(90.0), Some(Duration::from_millis(30)));
+        assert_eq!(stats.histogram(frame, 3), [0, 3, 2]);
+        assert!(stats.smoothed_fps().is_some_and(|fps| fps < 100.0 && fps > 33.0));
+    }
+
+    #[test]
+    fn fixed_step() {
+
+        let mut fixed = FixedStep::new(Duration::from_millis(10)).with_max_steps(3);
+        let mut instant = Instant::now();
+
+        assert_eq!(fixed.advance(instant).steps, 0);
+
+        instant += Duration::from_millis(25);
+        let steps = fixed.advance(instant);
+        assert_eq!(steps.steps, 2);
+        assert!((steps.alpha - 0.5).abs() < 1e-9);
+
+        instant += Duration::from_millis(5);
+        assert_eq!(fixed.advance(instant).steps, 1);
+        assert!(fixed.alpha().abs() < 1e-9);
+
+        // spiral of death
+        instant += Duration::from_millis(100);
+        assert_eq!(fixed.advance(instant).steps, 3);
+        assert_eq!(fixed.dropped, 7);
+    }
+
+    #[test]
+    fn fixed_step_rates() {
+        assert_eq!(FixedStep::from_hz(50.0).map(|fixed| fixed.step), Some(Duration::from_millis(20)));
+        for hz in [0.0, -60.0, f64::NAN, f64::INFINITY] {
+            assert!(FixedStep::from_hz(hz).is_none());
+        }
+    }
+
+    #[test]
+    fn app_clock() {
+
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut clock = AppClock::new_at(start);
+
+        assert_eq!(clock.elapsed_at(at(100)), Duration::from_millis(100));
+
+        clock.pause_at(at(100));
+        assert_eq!(clock.elapsed_at(at(500)), Duration::from_millis(100));
+        assert_eq!(clock.wall_instant(Duration::from_millis(200), at(500)), None);
+
+        clock.resume_at(at(500));
+        clock.set_scale_at(2.0, at(600));
+        assert_eq!(clock.elapsed_at(at(700)), Duration::from_millis(400));
+        assert_eq!(clock.instant_at(at(700)), at(400));
+        assert_eq!(clock.wall_instant(Duration::from_millis(600), at(700)), Some(at(800)));
+
+        // intervals driven by app instants
+        let interval = StepInterval::new_at(Duration::from_millis(100), clock.instant_at(at(700)));
+        assert_eq!(interval.elapsed(clock.instant_at(at(750))), 1);
+    }
+}
@@ -0,0 +1,209 @@
+use winit::{
+    event::{WindowEvent, Touch, TouchPhase, ElementState, MouseButton, DeviceId},
+    keyboard::ModifiersState,
+    dpi::{PhysicalPosition, PhysicalSize},
+};
+use std::vec::Drain;
+
+
+// ids of the synthetic touches, chosen to not collide with real touch ids
+pub const EMULATED_TOUCH_ID: u64 = u64::MAX - 1;
+pub const EMULATED_MIRROR_TOUCH_ID: u64 = u64::MAX - 2;
+
+pub fn is_emulated_touch(touch: &Touch) -> bool {
+    touch.id == EMULATED_TOUCH_ID || touch.id == EMULATED_MIRROR_TOUCH_ID
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MirrorPivot {
+    WindowCenter,
+    Fixed(PhysicalPosition<f64>),
+}
+
+
+// converts mouse input into synthetic touches,
+// with the mirror modifiers held a second finger is mirrored around the pivot to emulate pinch and rotate
+
+#[derive(Debug, Clone)]
+pub struct TouchEmulator {
+    pub button: MouseButton,
+    pub mirror_modifiers: ModifiersState,
+    pub pivot: MirrorPivot,
+    modifiers: ModifiersState,
+    cursor: Option<PhysicalPosition<f64>>,
+    window_size: Option<PhysicalSize<u32>>,
+    pressed: Option<DeviceId>,
+    mirror: Option<PhysicalPosition<f64>>, // pivot of the active mirror touch
+    events: Vec<Touch>,
+}
+
+impl Default for TouchEmulator {
+    fn default() -> Self { Self::new() }
+}
+
+impl TouchEmulator {
+
+    pub fn new() -> Self {
+        Self {
+            button: MouseButton::Left, mirror_modifiers: ModifiersState::SHIFT, pivot: MirrorPivot::WindowCenter,
+            modifiers: ModifiersState::empty(), cursor: None, window_size: None,
+            pressed: None, mirror: None, events: Vec::new(),
+        }
+    }
+
+    pub fn with_button(mut self, button: MouseButton) -> Self { self.button = button; self }
+    pub fn with_mirror_modifiers(mut self, modifiers: ModifiersState) -> Self { self.mirror_modifiers = modifiers; self }
+    pub fn with_pivot(mut self, pivot: MirrorPivot) -> Self { self.pivot = pivot; self }
+
+    pub fn set_window_size(&mut self, size: PhysicalSize<u32>) { self.window_size = Some(size) }
+
+    pub fn is_pressed(&self) -> bool { self.pressed.is_some() }
+    pub fn is_mirroring(&self) -> bool { self.mirror.is_some() }
+
+    // synthetic touches for the event, feed them to e.g. Touches::update
+    pub fn update(&mut self, event: &WindowEvent) -> Drain<'_, Touch> {
+
+        match event {
+
+            WindowEvent::Resized(size) => self.window_size = Some(*size),
+
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+
+                if let Some(device_id) = self.pressed {
+                    match (self.mirror_held(), self.mirror) {
+                        (true, None) => self.start_mirror(device_id),
+                        (false, Some(_)) => self.end_mirror(device_id, TouchPhase::Ended),
+                        _ => {},
+                    }
+                }
+            },
+
+            WindowEvent::CursorMoved { device_id, position } => {
+                self.cursor = Some(*position);
+
+                if self.pressed.is_some() {
+                    self.emit(*device_id, TouchPhase::Moved, EMULATED_TOUCH_ID, *position);
+                    if let Some(pivot) = self.mirror {
+                        self.emit(*device_id, TouchPhase::Moved, EMULATED_MIRROR_TOUCH_ID, mirrored(*position, pivot));
+                    }
+                }
+            },
+
+            WindowEvent::MouseInput { device_id, state, button } if *button == self.button => {
+                match (state, self.pressed, self.cursor) {
+                    (ElementState::Pressed, None, Some(position)) => {
+                        self.pressed = Some(*device_id);
+                        self.emit(*device_id, TouchPhase::Started, EMULATED_TOUCH_ID, position);
+                        if self.mirror_held() { self.start_mirror(*device_id) }
+                    },
+                    (ElementState::Released, Some(_), _) => self.end(*device_id, TouchPhase::Ended),
+                    _ => {},
+                }
+            },
+
+            WindowEvent::CursorLeft { device_id } => {
+                if self.pressed.is_some() { self.end(*device_id, TouchPhase::Cancelled) }
+                self.cursor = None;
+            },
+
+            WindowEvent::Focused(false) => {
+                if let Some(device_id) = self.pressed { self.end(device_id, TouchPhase::Cancelled) }
+                self.modifiers = ModifiersState::empty();
+            },
+
+            _ => {},
+        }
+
+        self.events.drain(..)
+    }
+
+
+    fn mirror_held(&self) -> bool {
+        !self.mirror_modifiers.is_empty() && self.modifiers.contains(self.mirror_modifiers)
+    }
+
+    fn pivot_position(&self) -> Option<PhysicalPosition<f64>> {
+        match self.pivot {
+            MirrorPivot::WindowCenter => self.window_size.map(|size| {
+                PhysicalPosition::new(size.width as f64 / 2.0, size.height as f64 / 2.0)
+            }),
+            MirrorPivot::Fixed(position) => Some(position),
+        }
+    }
+
+    fn start_mirror(&mut self, device_id: DeviceId) {
+        if let (Some(pivot), Some(position)) = (self.pivot_position(), self.cursor) {
+            self.mirror = Some(pivot);
+            self.emit(device_id, TouchPhase::Started, EMULATED_MIRROR_TOUCH_ID, mirrored(position, pivot));
+        }
+    }
+
+    fn end_mirror(&mut self, device_id: DeviceId, phase: TouchPhase) {
+        if let (Some(pivot), Some(position)) = (self.mirror.take(), self.cursor) {
+            self.emit(device_id, phase, EMULATED_MIRROR_TOUCH_ID, mirrored(position, pivot));
+        }
+    }
+
+    fn end(&mut self, device_id: DeviceId, phase: TouchPhase) {
+        self.end_mirror(device_id, phase);
+        if let (Some(_), Some(position)) = (self.pressed.take(), self.cursor) {
+            self.emit(device_id, phase, EMULATED_TOUCH_ID, position);
+        }
+    }
+
+    fn emit(&mut self, device_id: DeviceId, phase: TouchPhase, id: u64, location: PhysicalPosition<f64>) {
+        self.events.push(Touch { device_id, phase, location, force: None, id });
+    }
+}
+
+
+fn mirrored(position: PhysicalPosition<f64>, pivot: PhysicalPosition<f64>) -> PhysicalPosition<f64> {
+    PhysicalPosition::new(2.0 * pivot.x - position.x, 2.0 * pivot.y - position.y)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::touches::Touches;
+
+    const DEVICE_ID: DeviceId = DeviceId::dummy();
+
+    #[test]
+    fn mirrored_pinch() {
+
+        let mut emulator = TouchEmulator::new();
+        let mut touches = Touches::<()>::new();
+
+        let moved = |x, y| WindowEvent::CursorMoved { device_id: DEVICE_ID, position: PhysicalPosition::new(x, y) };
+        let button = |state| WindowEvent::MouseInput { device_id: DEVICE_ID, state, button: MouseButton::Left };
+
+        for event in [
+            WindowEvent::Resized(PhysicalSize::new(200, 200)),
+            WindowEvent::ModifiersChanged(ModifiersState::SHIFT.into()),
+            moved(120.0, 100.0),
+            button(ElementState::Pressed),
+            moved(140.0, 100.0),
+        ] {
+            emulator.update(&event).for_each(|touch| touches.update(touch));
+        }
+
+        assert!(emulator.is_mirroring());
+        assert_eq!(touches.len(), 2);
+        assert!((touches.transform().scale - 2.0).abs() < 1e-4);
+
+        // releasing the modifier lifts the second finger only
+        let events: Vec<_> = emulator.update(&WindowEvent::ModifiersChanged(ModifiersState::empty().into())).collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].id, events[0].phase), (EMULATED_MIRROR_TOUCH_ID, TouchPhase::Ended));
+
+        let events: Vec<_> = emulator.update(&button(ElementState::Released)).collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].id, events[0].phase), (EMULATED_TOUCH_ID, TouchPhase::Ended));
+        assert!(!emulator.is_pressed());
+    }
+}
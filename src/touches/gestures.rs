@@ -0,0 +1,957 @@
+use winit::event::{WindowEvent, Touch, TouchPhase};
+use std::vec::Drain;
+use crate::time::{Instant, Duration};
+use super::*;
+
+
+// configuration, distances in logical pixels
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GestureConfig {
+    pub scale_factor: f32, // touch units per logical pixel, follows the coordinates of a GestureArena
+    pub slop: f32, // movement tolerance of taps and long presses
+    pub tap_timeout: Duration,
+    pub double_tap_timeout: Duration,
+    pub double_tap_slop: f32,
+    pub long_press_duration: Duration,
+    pub swipe_min_velocity: f32, // per second
+    pub swipe_max_duration: Duration,
+    pub pinch_slop: f32,
+    pub rotate_slop: f32, // radians
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            scale_factor: 1.0,
+            slop: 10.0,
+            tap_timeout: Duration::from_millis(300),
+            double_tap_timeout: Duration::from_millis(300),
+            double_tap_slop: 40.0,
+            long_press_duration: Duration::from_millis(500),
+            swipe_min_velocity: 600.0,
+            swipe_max_duration: Duration::from_millis(500),
+            pinch_slop: 16.0,
+            rotate_slop: 0.15,
+        }
+    }
+}
+
+impl GestureConfig {
+    // logical distance to touch coordinates
+    pub fn dist(&self, logical: f32) -> f32 { logical * self.scale_factor }
+}
+
+
+// gestures
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GestureKind { Tap, DoubleTap, LongPress, Pan, Swipe, Pinch, Rotate }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GesturePhase {
+    Recognized, // discrete gestures
+    Began, Changed, Ended, Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwipeDirection { Left, Right, Up, Down }
+
+impl SwipeDirection {
+    pub fn from_vector(vector: TouchPos) -> Self {
+        if vector.x.abs() >= vector.y.abs() {
+            if vector.x < 0.0 { Self::Left } else { Self::Right }
+        } else {
+            if vector.y < 0.0 { Self::Up } else { Self::Down }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    Tap { location: TouchPos, fingers: usize },
+    DoubleTap { location: TouchPos },
+    LongPress { location: TouchPos },
+    Pan { location: TouchPos, delta: TouchPos, translation: TouchPos, fingers: usize },
+    Swipe { direction: SwipeDirection, velocity: TouchPos, fingers: usize },
+    Pinch { center: TouchPos, scale: f32, delta_scale: f32 },
+    Rotate { center: TouchPos, angle: f32, delta_angle: f32 },
+}
+
+impl Gesture {
+    pub fn kind(&self) -> GestureKind {
+        match self {
+            Self::Tap {..} => GestureKind::Tap,
+            Self::DoubleTap {..} => GestureKind::DoubleTap,
+            Self::LongPress {..} => GestureKind::LongPress,
+            Self::Pan {..} => GestureKind::Pan,
+            Self::Swipe {..} => GestureKind::Swipe,
+            Self::Pinch {..} => GestureKind::Pinch,
+            Self::Rotate {..} => GestureKind::Rotate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureEvent {
+    pub phase: GesturePhase,
+    pub gesture: Gesture,
+    pub instant: Instant,
+}
+
+
+// recognizers
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Recognition {
+    Pending,
+    Failed,
+    Recognized(Gesture),
+    Began(Gesture),
+    Changed(Gesture),
+    Ended(Gesture),
+    Cancelled(Gesture),
+}
+
+pub struct GestureInput<'a> {
+    pub touches: &'a Touches, // registers of the current sequence, ended ones are kept until it ends
+    pub touch: &'a Touch,
+    pub instant: Instant,
+    pub config: &'a GestureConfig,
+}
+
+impl GestureInput<'_> {
+
+    pub fn active(&self) -> impl Iterator<Item=&TouchRegister> + Clone { self.touches.iter().filter(|reg| !reg.ended()) }
+
+    pub fn active_count(&self) -> usize { self.active().count() }
+
+    // first touch of a sequence started
+    pub fn sequence_started(&self) -> bool { self.touch.phase == TouchPhase::Started && self.touches.len() == 1 }
+
+    // last touch of a sequence ended
+    pub fn sequence_ended(&self) -> bool {
+        matches!(self.touch.phase, TouchPhase::Ended | TouchPhase::Cancelled) && self.active_count() == 0
+    }
+
+    // location of the updated touch in the coordinates of the touches
+    pub fn location(&self) -> TouchPos {
+        self.touches.iter().rev().find(|reg| reg.id() == self.touch.id)
+            .map_or_else(|| self.touches.coords().from_physical(self.touch.location), |reg| reg.location())
+    }
+
+    pub fn moved_beyond(&self, distance: f32) -> bool {
+        self.touches.iter().any(|reg| reg.delta_location().length() > distance)
+    }
+}
+
+pub trait GestureRecognizer {
+    fn kind(&self) -> GestureKind;
+    fn update(&mut self, input: &GestureInput) -> Recognition;
+    fn poll(&mut self, _instant: Instant, _config: &GestureConfig) -> Recognition { Recognition::Pending }
+    fn deadline(&self) -> Option<Instant> { None }
+    fn reset(&mut self);
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct TapRecognizer {
+    start: Option<Instant>,
+    fingers: usize,
+}
+
+impl GestureRecognizer for TapRecognizer {
+
+    fn kind(&self) -> GestureKind { GestureKind::Tap }
+
+    fn update(&mut self, input: &GestureInput) -> Recognition {
+
+        if input.sequence_started() { self.start = Some(input.instant) }
+        self.fingers = self.fingers.max(input.active_count());
+
+        let Some(start) = self.start else { return Recognition::Failed };
+
+        if
+            input.touch.phase == TouchPhase::Cancelled ||
+            input.instant - start > input.config.tap_timeout ||
+            input.moved_beyond(input.config.dist(input.config.slop))
+        {
+            return Recognition::Failed;
+        }
+
+        if input.sequence_ended() {
+            let gesture = Gesture::Tap { location: TouchRegister::mean_location(input.touches.iter()), fingers: self.fingers };
+            self.reset();
+            return Recognition::Recognized(gesture);
+        }
+
+        Recognition::Pending
+    }
+
+    fn reset(&mut self) { *self = Self::default() }
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct DoubleTapRecognizer {
+    start: Option<Instant>,
+    first_tap: Option<(Instant, TouchPos)>,
+    timeout: Duration,
+}
+
+impl GestureRecognizer for DoubleTapRecognizer {
+
+    fn kind(&self) -> GestureKind { GestureKind::DoubleTap }
+
+    fn update(&mut self, input: &GestureInput) -> Recognition {
+
+        let config = input.config;
+
+        if input.sequence_started() {
+            if let Some((end, location)) = self.first_tap && (
+                input.instant - end > config.double_tap_timeout ||
+                input.location().distance(location) > config.dist(config.double_tap_slop)
+            ) {
+                return Recognition::Failed;
+            }
+            self.start = Some(input.instant);
+        }
+
+        let Some(start) = self.start else { return Recognition::Failed };
+
+        if
+            input.touch.phase == TouchPhase::Cancelled || input.touches.len() > 1 ||
+            input.instant - start > config.tap_timeout ||
+            input.moved_beyond(config.dist(config.slop))
+        {
+            return Recognition::Failed;
+        }
+
+        if input.sequence_ended() {
+            let location = TouchRegister::mean_location(input.touches.iter());
+            if self.first_tap.is_some() {
+                self.reset();
+                return Recognition::Recognized(Gesture::DoubleTap { location });
+            }
+            self.first_tap = Some((input.instant, location));
+            self.timeout = config.double_tap_timeout;
+            self.start = None;
+        }
+
+        Recognition::Pending
+    }
+
+    fn poll(&mut self, instant: Instant, _config: &GestureConfig) -> Recognition {
+        match self.deadline() {
+            Some(deadline) if instant >= deadline => Recognition::Failed,
+            _ => Recognition::Pending,
+        }
+    }
+
+    // no second tap started in time
+    fn deadline(&self) -> Option<Instant> {
+        self.first_tap.filter(|_| self.start.is_none()).map(|(end, _)| end + self.timeout)
+    }
+
+    fn reset(&mut self) { *self = Self::default() }
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct LongPressRecognizer {
+    start: Option<(Instant, TouchPos)>,
+    duration: Duration,
+    active: bool,
+}
+
+impl GestureRecognizer for LongPressRecognizer {
+
+    fn kind(&self) -> GestureKind { GestureKind::LongPress }
+
+    fn update(&mut self, input: &GestureInput) -> Recognition {
+
+        if input.sequence_started() {
+            self.start = Some((input.instant, input.location()));
+            self.duration = input.config.long_press_duration;
+        }
+
+        if self.start.is_none() || input.touches.len() > 1 {
+            return self.fail_or_cancel();
+        }
+
+        let location = input.location();
+
+        match input.touch.phase {
+            TouchPhase::Started => Recognition::Pending,
+            TouchPhase::Moved if self.active => Recognition::Changed(Gesture::LongPress { location }),
+            TouchPhase::Moved if input.moved_beyond(input.config.dist(input.config.slop)) => Recognition::Failed,
+            TouchPhase::Moved => Recognition::Pending,
+            TouchPhase::Ended if self.active => { self.reset(); Recognition::Ended(Gesture::LongPress { location }) },
+            TouchPhase::Ended | TouchPhase::Cancelled => self.fail_or_cancel(),
+        }
+    }
+
+    fn poll(&mut self, instant: Instant, _config: &GestureConfig) -> Recognition {
+        match (self.deadline(), self.start) {
+            (Some(deadline), Some((_, location))) if instant >= deadline => {
+                self.active = true;
+                Recognition::Began(Gesture::LongPress { location })
+            },
+            _ => Recognition::Pending,
+        }
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        self.start.filter(|_| !self.active).map(|(start, _)| start + self.duration)
+    }
+
+    fn reset(&mut self) { *self = Self::default() }
+}
+
+impl LongPressRecognizer {
+    fn fail_or_cancel(&mut self) -> Recognition {
+        let res = match (self.active, self.start) {
+            (true, Some((_, location))) => Recognition::Cancelled(Gesture::LongPress { location }),
+            _ => Recognition::Failed,
+        };
+        self.reset();
+        res
+    }
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct PanRecognizer {
+    active: bool,
+    fingers: usize,
+    last_mean: TouchPos,
+    translation: TouchPos,
+}
+
+impl GestureRecognizer for PanRecognizer {
+
+    fn kind(&self) -> GestureKind { GestureKind::Pan }
+
+    fn update(&mut self, input: &GestureInput) -> Recognition {
+
+        let fingers = input.active_count();
+        let location = TouchRegister::mean_location(input.active());
+
+        if !self.active {
+
+            if fingers > 0 && input.moved_beyond(input.config.dist(input.config.slop)) {
+                self.active = true;
+                self.fingers = fingers;
+                self.last_mean = location;
+                self.translation = location - TouchRegister::mean_ref_location(input.active());
+                return Recognition::Began(Gesture::Pan { location, delta: self.translation, translation: self.translation, fingers });
+            }
+
+            return if input.sequence_ended() { Recognition::Failed } else { Recognition::Pending };
+        }
+
+        if input.touch.phase == TouchPhase::Cancelled {
+            let res = Recognition::Cancelled(self.gesture(TouchPos::ZERO));
+            self.reset();
+            return res;
+        }
+
+        if fingers == 0 {
+            let res = Recognition::Ended(self.gesture(TouchPos::ZERO));
+            self.reset();
+            return res;
+        }
+
+        if fingers != self.fingers {
+            // rebase on finger change
+            self.fingers = fingers;
+            self.last_mean = location;
+            return Recognition::Changed(self.gesture(TouchPos::ZERO));
+        }
+
+        let delta = location - self.last_mean;
+        self.last_mean = location;
+        self.translation += delta;
+
+        Recognition::Changed(self.gesture(delta))
+    }
+
+    fn reset(&mut self) { *self = Self::default() }
+}
+
+impl PanRecognizer {
+    fn gesture(&self, delta: TouchPos) -> Gesture {
+        Gesture::Pan { location: self.last_mean, delta, translation: self.translation, fingers: self.fingers }
+    }
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct SwipeRecognizer {
+    start: Option<Instant>,
+    fingers: usize,
+}
+
+impl GestureRecognizer for SwipeRecognizer {
+
+    fn kind(&self) -> GestureKind { GestureKind::Swipe }
+
+    fn update(&mut self, input: &GestureInput) -> Recognition {
+
+        if input.sequence_started() { self.start = Some(input.instant) }
+        self.fingers = self.fingers.max(input.active_count());
+
+        let Some(start) = self.start else { return Recognition::Failed };
+
+        let duration = input.instant - start;
+
+        if input.touch.phase == TouchPhase::Cancelled || duration > input.config.swipe_max_duration {
+            return Recognition::Failed;
+        }
+
+        if input.sequence_ended() {
+
+            let distance = TouchRegister::mean_delta_location(input.touches.iter());
+            let velocity = input.touches.velocity();
+
+            let config = input.config;
+            let fingers = self.fingers;
+            self.reset();
+
+            return if distance.length() > config.dist(config.slop) && velocity.length() >= config.dist(config.swipe_min_velocity) {
+                Recognition::Recognized(Gesture::Swipe { direction: SwipeDirection::from_vector(distance), velocity, fingers })
+            } else {
+                Recognition::Failed
+            };
+        }
+
+        Recognition::Pending
+    }
+
+    fn reset(&mut self) { *self = Self::default() }
+}
+
+
+// two-finger gestures track the first two active touches
+
+fn first_two<'a>(input: &'a GestureInput) -> Option<(&'a TouchRegister, &'a TouchRegister)> {
+    let mut active = input.active();
+    Some((active.next()?, active.next()?))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PinchRecognizer {
+    active: bool,
+    ids: Option<(u64, u64)>,
+    base_scale: f32, // accumulated scale of previous pairs
+    ref_distance: f32,
+    scale: f32,
+    center: TouchPos,
+}
+
+impl GestureRecognizer for PinchRecognizer {
+
+    fn kind(&self) -> GestureKind { GestureKind::Pinch }
+
+    fn update(&mut self, input: &GestureInput) -> Recognition {
+
+        let Some((a, b)) = first_two(input) else {
+            let res = if self.active {
+                Recognition::Ended(Gesture::Pinch { center: self.center, scale: self.scale, delta_scale: 1.0 })
+            } else if input.sequence_ended() { Recognition::Failed } else { Recognition::Pending };
+            if !matches!(res, Recognition::Pending) { self.reset() }
+            return res;
+        };
+
+        let distance = a.location().distance(b.location()).max(f32::EPSILON);
+        self.center = (a.location() + b.location()) / 2.0;
+
+        if self.ids != Some((a.id(), b.id())) {
+            // new pair, rebase
+            if self.ids.is_none() { self.base_scale = 1.0; self.scale = 1.0 }
+            self.ids = Some((a.id(), b.id()));
+            self.base_scale = self.scale;
+            self.ref_distance = distance;
+            return Recognition::Pending;
+        }
+
+        let previous = self.scale;
+        self.scale = self.base_scale * distance / self.ref_distance;
+        let gesture = Gesture::Pinch { center: self.center, scale: self.scale, delta_scale: self.scale / previous };
+
+        if input.touch.phase == TouchPhase::Cancelled {
+            let res = if self.active { Recognition::Cancelled(gesture) } else { Recognition::Failed };
+            self.reset();
+            return res;
+        }
+
+        if self.active {
+            Recognition::Changed(gesture)
+        }
+        else if (distance - self.ref_distance).abs() > input.config.dist(input.config.pinch_slop) {
+            self.active = true;
+            Recognition::Began(gesture)
+        }
+        else { Recognition::Pending }
+    }
+
+    fn reset(&mut self) { *self = Self::default() }
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct RotateRecognizer {
+    active: bool,
+    ids: Option<(u64, u64)>,
+    last_angle: f32,
+    angle: f32,
+    center: TouchPos,
+}
+
+impl GestureRecognizer for RotateRecognizer {
+
+    fn kind(&self) -> GestureKind { GestureKind::Rotate }
+
+    fn update(&mut self, input: &GestureInput) -> Recognition {
+
+        let Some((a, b)) = first_two(input) else {
+            let res = if self.active {
+                Recognition::Ended(Gesture::Rotate { center: self.center, angle: self.angle, delta_angle: 0.0 })
+            } else if input.sequence_ended() { Recognition::Failed } else { Recognition::Pending };
+            if !matches!(res, Recognition::Pending) { self.reset() }
+            return res;
+        };
+
+        let angle = a.diff_angle(b);
+        self.center = (a.location() + b.location()) / 2.0;
+
+        if self.ids != Some((a.id(), b.id())) {
+            // new pair, rebase
+            self.ids = Some((a.id(), b.id()));
+            self.last_angle = angle;
+            return Recognition::Pending;
+        }
+
+        let delta_angle = (angle - self.last_angle).angle_as_delta();
+        self.last_angle = angle;
+        self.angle += delta_angle;
+
+        let gesture = Gesture::Rotate { center: self.center, angle: self.angle, delta_angle };
+
+        if input.touch.phase == TouchPhase::Cancelled {
+            let res = if self.active { Recognition::Cancelled(gesture) } else { Recognition::Failed };
+            self.reset();
+            return res;
+        }
+
+        if self.active {
+            Recognition::Changed(gesture)
+        }
+        else if self.angle.abs() > input.config.rotate_slop {
+            self.active = true;
+            Recognition::Began(gesture)
+        }
+        else { Recognition::Pending }
+    }
+
+    fn reset(&mut self) { *self = Self::default() }
+}
+
+
+// arena, resolves conflicts between recognizers
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArenaState {
+    Possible,
+    Active,
+    Failed,
+    Held(Gesture), // recognized, waiting for other recognizers to fail
+}
+
+struct ArenaEntry {
+    recognizer: Box<dyn GestureRecognizer>,
+    state: ArenaState,
+}
+
+pub struct GestureArena {
+    pub config: GestureConfig,
+    touches: Touches,
+    entries: Vec<ArenaEntry>,
+    simultaneous: Vec<(GestureKind, GestureKind)>,
+    requires_failure: Vec<(GestureKind, GestureKind)>,
+    events: Vec<GestureEvent>,
+    #[cfg(feature = "timeout")] timeout_id: Option<u64>,
+}
+
+impl GestureArena {
+
+    pub fn new(config: GestureConfig) -> Self {
+        Self {
+            touches: Touches::new().with_coords(TouchCoords { scale_factor: config.scale_factor as f64, ..Default::default() }),
+            config, entries: Vec::new(),
+            simultaneous: Vec::new(), requires_failure: Vec::new(), events: Vec::new(),
+            #[cfg(feature = "timeout")] timeout_id: None,
+        }
+    }
+
+    // all recognizers, taps wait for double taps to fail, transforms may run together
+    pub fn with_default_recognizers(config: GestureConfig) -> Self {
+        use GestureKind::*;
+        Self::new(config)
+            .with_recognizer(DoubleTapRecognizer::default())
+            .with_recognizer(TapRecognizer::default())
+            .with_recognizer(LongPressRecognizer::default())
+            .with_recognizer(PinchRecognizer::default())
+            .with_recognizer(RotateRecognizer::default())
+            .with_recognizer(PanRecognizer::default())
+            .with_recognizer(SwipeRecognizer::default())
+            .with_requires_failure(Tap, DoubleTap)
+            .with_simultaneous(Pinch, Rotate)
+            .with_simultaneous(Pan, Pinch)
+            .with_simultaneous(Pan, Rotate)
+            .with_simultaneous(Pan, Swipe)
+    }
+
+    // earlier recognizers take precedence
+    pub fn with_recognizer(mut self, recognizer: impl GestureRecognizer + 'static) -> Self {
+        self.entries.push(ArenaEntry { recognizer: Box::new(recognizer), state: ArenaState::Possible });
+        self
+    }
+
+    pub fn with_simultaneous(mut self, a: GestureKind, b: GestureKind) -> Self {
+        self.simultaneous.push((a, b));
+        self
+    }
+
+    // recognitions of kind are held back until the other kind failed
+    pub fn with_requires_failure(mut self, kind: GestureKind, other: GestureKind) -> Self {
+        self.requires_failure.push((kind, other));
+        self
+    }
+
+    pub fn touches(&self) -> &Touches { &self.touches }
+
+    pub fn with_coords(mut self, coords: TouchCoords) -> Self { self.set_coords(coords); self }
+
+    // gesture positions and the thresholds of the config share these coordinates
+    pub fn set_coords(&mut self, coords: TouchCoords) {
+        self.touches.set_coords(coords);
+        self.update_config_scale();
+    }
+
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.touches.set_scale_factor(scale_factor);
+        self.update_config_scale();
+    }
+
+    fn update_config_scale(&mut self) { self.config.scale_factor = self.touches.coords().logical_scale() as f32 }
+
+    fn is_simultaneous(&self, a: GestureKind, b: GestureKind) -> bool {
+        self.simultaneous.iter().any(|pair| *pair == (a, b) || *pair == (b, a))
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.entries.iter()
+            .filter(|entry| entry.state != ArenaState::Failed)
+            .filter_map(|entry| entry.recognizer.deadline())
+            .min()
+    }
+
+    pub fn update(&mut self, touch: Touch) -> Drain<'_, GestureEvent> {
+        self.update_at(touch, Instant::now())
+    }
+
+    pub fn update_at(&mut self, touch: Touch, instant: Instant) -> Drain<'_, GestureEvent> {
+        self.process_touch(touch, instant);
+        self.events.drain(..)
+    }
+
+    // handles touches and keeps the coordinates of the touches up to date
+    pub fn window_event(&mut self, event: &WindowEvent) -> Drain<'_, GestureEvent> {
+        self.window_event_at(event, Instant::now())
+    }
+
+    pub fn window_event_at(&mut self, event: &WindowEvent, instant: Instant) -> Drain<'_, GestureEvent> {
+        self.process_window_event(event, instant);
+        self.events.drain(..)
+    }
+
+    pub fn poll(&mut self, instant: Instant) -> Drain<'_, GestureEvent> {
+        self.process_deadlines(instant);
+        self.events.drain(..)
+    }
+
+    fn process_window_event(&mut self, event: &WindowEvent, instant: Instant) {
+        match event {
+            WindowEvent::Touch(touch) => self.process_touch(*touch, instant),
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => self.set_scale_factor(*scale_factor),
+            event => {
+                self.touches.window_event_at(event, instant);
+                self.update_config_scale();
+            },
+        }
+    }
+
+    fn process_touch(&mut self, touch: Touch, instant: Instant) {
+
+        if touch.phase == TouchPhase::Started && self.touches.is_empty() {
+            // new sequence
+            for entry in self.entries.iter_mut().filter(|entry| entry.state == ArenaState::Failed) {
+                entry.recognizer.reset();
+                entry.state = ArenaState::Possible;
+            }
+        }
+
+        self.touches.update_at(touch, instant);
+
+        for i in 0..self.entries.len() {
+
+            if matches!(self.entries[i].state, ArenaState::Failed | ArenaState::Held(_)) { continue }
+
+            let input = GestureInput { touches: &self.touches, touch: &touch, instant, config: &self.config };
+            let recognition = self.entries[i].recognizer.update(&input);
+
+            self.resolve(i, recognition, instant);
+        }
+
+        if self.touches.ended() == self.touches.len() {
+            self.touches.clear(|_| true);
+        }
+    }
+
+    fn process_deadlines(&mut self, instant: Instant) {
+
+        for i in 0..self.entries.len() {
+
+            if matches!(self.entries[i].state, ArenaState::Failed | ArenaState::Held(_)) { continue }
+
+            let recognition = self.entries[i].recognizer.poll(instant, &self.config);
+            self.resolve(i, recognition, instant);
+        }
+    }
+
+    fn emit(&mut self, phase: GesturePhase, gesture: Gesture, instant: Instant) {
+        self.events.push(GestureEvent { phase, gesture, instant });
+    }
+
+    fn fail(&mut self, i: usize, instant: Instant) {
+
+        self.entries[i].recognizer.reset();
+        self.entries[i].state = ArenaState::Failed;
+
+        // release held recognitions which were waiting for this one
+        for j in 0..self.entries.len() {
+            if let ArenaState::Held(gesture) = self.entries[j].state && !self.waits_for_failure(j) {
+                self.entries[j].state = ArenaState::Possible;
+                self.emit(GesturePhase::Recognized, gesture, instant);
+            }
+        }
+    }
+
+    fn waits_for_failure(&self, i: usize) -> bool {
+        let kind = self.entries[i].recognizer.kind();
+        self.entries.iter().any(|other| {
+            other.state != ArenaState::Failed &&
+            self.requires_failure.contains(&(kind, other.recognizer.kind()))
+        })
+    }
+
+    // returns false if a conflicting recognizer is already active
+    fn claim(&mut self, i: usize, instant: Instant) -> bool {
+
+        let kind = self.entries[i].recognizer.kind();
+
+        let blocked = self.entries.iter().enumerate().any(|(j, other)| {
+            j != i && other.state == ArenaState::Active && !self.is_simultaneous(kind, other.recognizer.kind())
+        });
+
+        if blocked {
+            self.fail(i, instant);
+            return false;
+        }
+
+        // competitors lose
+        for j in 0..self.entries.len() {
+            if j != i && matches!(self.entries[j].state, ArenaState::Possible | ArenaState::Held(_)) &&
+                !self.is_simultaneous(kind, self.entries[j].recognizer.kind())
+            {
+                self.fail(j, instant);
+            }
+        }
+
+        true
+    }
+
+    fn resolve(&mut self, i: usize, recognition: Recognition, instant: Instant) {
+        match recognition {
+
+            Recognition::Pending => {},
+
+            Recognition::Failed => {
+                if self.entries[i].state == ArenaState::Active {
+                    // treat as ended silently
+                    self.entries[i].state = ArenaState::Possible;
+                } else {
+                    self.fail(i, instant);
+                }
+            },
+
+            Recognition::Recognized(gesture) => {
+                if self.waits_for_failure(i) {
+                    self.entries[i].state = ArenaState::Held(gesture);
+                }
+                else if self.claim(i, instant) {
+                    self.emit(GesturePhase::Recognized, gesture, instant);
+                }
+            },
+
+            Recognition::Began(gesture) => {
+                if self.claim(i, instant) {
+                    self.entries[i].state = ArenaState::Active;
+                    self.emit(GesturePhase::Began, gesture, instant);
+                }
+            },
+
+            Recognition::Changed(gesture) => {
+                if self.entries[i].state == ArenaState::Active {
+                    self.emit(GesturePhase::Changed, gesture, instant);
+                }
+            },
+
+            Recognition::Ended(gesture) | Recognition::Cancelled(gesture) => {
+                if self.entries[i].state == ArenaState::Active {
+                    self.entries[i].state = ArenaState::Possible;
+                    let phase = if matches!(recognition, Recognition::Ended(_)) { GesturePhase::Ended } else { GesturePhase::Cancelled };
+                    self.emit(phase, gesture, instant);
+                }
+            },
+        }
+    }
+}
+
+
+// integration with app timeouts, so that e.g. long presses fire without further input
+
+#[cfg(feature = "timeout")]
+impl GestureArena {
+
+    pub fn with_timeout_id(mut self, timeout_id: u64) -> Self {
+        self.timeout_id = Some(timeout_id);
+        self
+    }
+
+    pub fn schedule<U: crate::EventLike>(&self, app_ctx: &mut crate::AppCtx<U>) {
+        if let Some(id) = self.timeout_id {
+            match self.deadline() {
+                Some(deadline) => { app_ctx.set_timeout(id, deadline); },
+                None => { app_ctx.cancel_timeout(id, None); },
+            }
+        }
+    }
+
+    // handles window and timeout events and reschedules the timeout
+    pub fn app_event<U: crate::EventLike>(&mut self, app_ctx: &mut crate::AppCtx<U>, event: &crate::Event<U>) -> Drain<'_, GestureEvent> {
+
+        match event {
+            crate::Event::WindowEvent(event) => self.process_window_event(event, Instant::now()),
+            crate::Event::Timeout { id, instant } if Some(*id) == self.timeout_id => self.process_deadlines(*instant),
+            _ => {},
+        }
+
+        self.schedule(app_ctx);
+        self.events.drain(..)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use winit::{event::DeviceId, dpi::PhysicalPosition};
+
+    fn touch(id: u64, phase: TouchPhase, x: f64, y: f64) -> Touch {
+        Touch { device_id: DeviceId::dummy(), phase, location: PhysicalPosition::new(x, y), force: None, id }
+    }
+
+    fn phases(events: Drain<'_, GestureEvent>) -> Vec<(GestureKind, GesturePhase)> {
+        events.map(|event| (event.gesture.kind(), event.phase)).collect()
+    }
+
+    #[test]
+    fn tap_waits_for_double_tap_to_fail() {
+
+        let mut arena = GestureArena::with_default_recognizers(GestureConfig::default());
+        let t0 = Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+
+        assert!(phases(arena.update_at(touch(0, TouchPhase::Started, 10.0, 10.0), t0)).is_empty());
+        assert!(phases(arena.update_at(touch(0, TouchPhase::Ended, 11.0, 10.0), ms(50))).is_empty());
+
+        assert_eq!(arena.deadline(), Some(ms(350)));
+        assert_eq!(phases(arena.poll(ms(350))), [(GestureKind::Tap, GesturePhase::Recognized)]);
+
+        // double tap
+        arena.update_at(touch(1, TouchPhase::Started, 10.0, 10.0), ms(400));
+        arena.update_at(touch(1, TouchPhase::Ended, 10.0, 10.0), ms(450));
+        arena.update_at(touch(2, TouchPhase::Started, 12.0, 10.0), ms(500));
+        assert_eq!(phases(arena.update_at(touch(2, TouchPhase::Ended, 12.0, 10.0), ms(550))), [(GestureKind::DoubleTap, GesturePhase::Recognized)]);
+        assert_eq!(arena.deadline(), None);
+    }
+
+    #[test]
+    fn long_press_fires_on_deadline() {
+
+        let mut arena = GestureArena::with_default_recognizers(GestureConfig::default());
+        let t0 = Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+
+        arena.update_at(touch(0, TouchPhase::Started, 10.0, 10.0), t0);
+        assert_eq!(arena.deadline(), Some(ms(500)));
+        assert_eq!(phases(arena.poll(ms(500))), [(GestureKind::LongPress, GesturePhase::Began)]);
+        assert_eq!(phases(arena.update_at(touch(0, TouchPhase::Ended, 10.0, 10.0), ms(600))), [(GestureKind::LongPress, GesturePhase::Ended)]);
+    }
+
+    #[test]
+    fn pan_then_swipe() {
+
+        let mut arena = GestureArena::with_default_recognizers(GestureConfig::default());
+        let t0 = Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+
+        arena.update_at(touch(0, TouchPhase::Started, 0.0, 0.0), t0);
+        assert_eq!(phases(arena.update_at(touch(0, TouchPhase::Moved, 50.0, 0.0), ms(20))), [(GestureKind::Pan, GesturePhase::Began)]);
+        assert_eq!(phases(arena.update_at(touch(0, TouchPhase::Moved, 100.0, 0.0), ms(40))), [(GestureKind::Pan, GesturePhase::Changed)]);
+
+        let events: Vec<_> = arena.update_at(touch(0, TouchPhase::Ended, 100.0, 0.0), ms(60)).collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].gesture.kind(), events[0].phase), (GestureKind::Pan, GesturePhase::Ended));
+        assert!(matches!(events[1].gesture, Gesture::Swipe { direction: SwipeDirection::Right, .. }));
+    }
+
+    #[test]
+    fn shared_coordinates() {
+
+        let coords = TouchCoords { space: TouchSpace::Logical, scale_factor: 2.0, window_size: winit::dpi::PhysicalSize::new(800, 600) };
+        let mut arena = GestureArena::with_default_recognizers(GestureConfig::default()).with_coords(coords);
+        let t0 = Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+
+        assert_eq!(arena.config.dist(10.0), 10.0);
+
+        // 70 physical pixels apart are 35 logical pixels, within the double-tap slop
+        arena.update_at(touch(0, TouchPhase::Started, 0.0, 0.0), t0);
+        arena.update_at(touch(0, TouchPhase::Ended, 0.0, 0.0), ms(50));
+        arena.update_at(touch(1, TouchPhase::Started, 70.0, 0.0), ms(100));
+        assert_eq!(phases(arena.update_at(touch(1, TouchPhase::Ended, 70.0, 0.0), ms(150))), [(GestureKind::DoubleTap, GesturePhase::Recognized)]);
+
+        arena.update_at(touch(2, TouchPhase::Started, 100.0, 40.0), ms(1000));
+        let events: Vec<_> = arena.poll(ms(1500)).collect();
+        assert_eq!(events.iter().map(|event| event.gesture).collect::<Vec<_>>(), [Gesture::LongPress { location: TouchPos::new(50.0, 20.0) }]);
+
+        // physical coordinates scale the thresholds instead
+        arena.set_coords(TouchCoords { space: TouchSpace::Physical, ..coords });
+        assert_eq!(arena.config.dist(10.0), 20.0);
+    }
+}
@@ -2,6 +2,29 @@
 use winit::{event::{Touch, TouchPhase, Force}, dpi::PhysicalPosition};
 use std::{slice, iter};
 
+mod gestures;
+pub use gestures::*;
+
+mod transform;
+pub use transform::*;
+
+mod velocity;
+pub use velocity::*;
+
+mod emulator;
+pub use emulator::*;
+
+mod space;
+pub use space::*;
+
+mod pen;
+pub use pen::*;
+
+mod pointer;
+pub use pointer::*;
+
+use crate::time::Instant;
+
 
 pub type TouchPos = glam::Vec2;
 
@@ -15,6 +38,8 @@ pub struct TouchRegister<T: 'static = ()> {
     location: TouchPos,
     pub ref_force: Option<f32>,
     force: Option<f32>,
+    samples: TouchSamples,
+    pen: PenState,
 }
 
 impl<T> TouchRegister<T> {
@@ -72,6 +97,7 @@ impl TouchExt for () {
 }
 
 
+// owned by the user, fed with Touches::window_event the coordinates follow the window
 #[derive(Debug, Clone, PartialEq)]
 pub struct Touches<
     T: TouchExt + 'static = (),
@@ -79,12 +105,13 @@ pub struct Touches<
     const MAX: usize = {usize::MAX},
 > {
     pub vec: Vec<TouchRegister<T>>,
+    coords: TouchCoords,
 }
 
 impl<T: TouchExt + 'static, const SHRINK_TO:usize, const MAX:usize> Touches<T, SHRINK_TO, MAX> {
 
     #[allow(clippy::new_without_default)]
-    pub fn new() -> Self { Self { vec: Vec::new() } }
+    pub fn new() -> Self { Self { vec: Vec::new(), coords: TouchCoords::default() } }
 
     pub fn len(&self) -> usize { self.vec.len() }
     pub fn is_empty(&self) -> bool { self.vec.is_empty() }
@@ -116,7 +143,9 @@ impl<T: TouchExt + 'static, const SHRINK_TO:usize, const MAX:usize> Touches<T, S
         self.iter_mut().filter(move |reg| reg.id == id)
     }
 
-    pub fn update(&mut self, touch: Touch) {
+    pub fn update(&mut self, touch: Touch) { self.update_at(touch, Instant::now()) }
+
+    pub fn update_at(&mut self, touch: Touch, instant: Instant) {
 
         match touch.phase {
 
@@ -127,7 +156,7 @@ impl<T: TouchExt + 'static, const SHRINK_TO:usize, const MAX:usize> Touches<T, S
 
                 if self.vec.len() < MAX {
 
-                    let location = TouchPos::from_physical_position(touch.location);
+                    let location = self.coords.from_physical(touch.location);
                     let force = touch.force.map(normalize_force);
 
                     self.vec.push(TouchRegister {
@@ -135,24 +164,32 @@ impl<T: TouchExt + 'static, const SHRINK_TO:usize, const MAX:usize> Touches<T, S
                         ext: T::new(touch.id, location, force),
                         ref_location: location, location,
                         ref_force: force, force,
+                        samples: TouchSamples::new(TouchSample { location, instant }),
+                        pen: PenState::from_touch(&touch),
                     });
                 }
             },
 
             TouchPhase::Moved => {
+                let location = self.coords.from_physical(touch.location);
                 self.by_id(touch.id).for_each(|reg| {
                     if !reg.ended {
-                        reg.location = TouchPos::from_physical_position(touch.location);
+                        reg.location = location;
                         reg.force = touch.force.map(normalize_force);
+                        reg.samples.push(TouchSample { location: reg.location, instant });
+                        reg.pen.update(&touch);
                     }
                 });
             },
 
             TouchPhase::Ended | TouchPhase::Cancelled => {
+                let location = self.coords.from_physical(touch.location);
                 self.by_id(touch.id).for_each(|reg| {
                     if !reg.ended {
-                        reg.location = TouchPos::from_physical_position(touch.location);
+                        reg.location = location;
                         reg.force = touch.force.map(normalize_force);
+                        reg.samples.push(TouchSample { location: reg.location, instant });
+                        reg.pen.update(&touch);
                     }
                     reg.ended = true;
                 });
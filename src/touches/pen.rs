@@ -0,0 +1,155 @@
+use winit::event::{Touch, Force};
+use std::f32::consts::FRAC_PI_2;
+use super::*;
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PointerKind {
+    #[default] Unknown,
+    Finger,
+    Pen,
+    Mouse,
+    Emulated, // from the TouchEmulator
+}
+
+
+// full pen state of a touch, fields are None where the platform does not report them
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PenState {
+    pub kind: PointerKind,
+    pub force: Option<Force>, // as reported
+    pub pressure: Option<f32>, // 0..1, as normalized by winit, accounting for the altitude where known
+    pub altitude: Option<f32>, // radians, 0 parallel to the surface, PI/2 perpendicular
+    pub barrel: Option<bool>,
+    pub eraser: Option<bool>,
+}
+
+impl PenState {
+
+    // winit only distinguishes a pen by its altitude, which is reported for styluses only
+    pub fn from_touch(touch: &Touch) -> Self {
+
+        let altitude = match touch.force {
+            Some(Force::Calibrated { altitude_angle, .. }) => altitude_angle.map(|a| a as f32),
+            _ => None,
+        };
+
+        let kind = if is_emulated_touch(touch) { PointerKind::Emulated }
+            else if altitude.is_some() { PointerKind::Pen }
+            else if touch.force.is_some() { PointerKind::Finger }
+            else { PointerKind::Unknown };
+
+        Self {
+            kind, altitude,
+            force: touch.force,
+            pressure: touch.force.map(|force| force.normalized().clamp(0.0, 1.0) as f32),
+            barrel: None, eraser: None,
+        }
+    }
+
+    pub fn is_pen(&self) -> bool { self.kind == PointerKind::Pen }
+
+    // angle from the surface normal, 0 perpendicular
+    pub fn tilt(&self) -> Option<f32> { self.altitude.map(|a| FRAC_PI_2 - a) }
+
+    // force along the pen axis relative to an average touch, as calibrated by the platform
+    pub fn calibrated_force(&self) -> Option<f32> {
+        match self.force {
+            Some(Force::Calibrated { force, .. }) => Some(force as f32),
+            _ => None,
+        }
+    }
+
+    pub fn pressure_with(&self, curve: &PressureCurve) -> Option<f32> { self.pressure.map(|p| curve.apply(p)) }
+
+    // keep pen state which is not part of touch events, when updating from one
+    pub(super) fn update(&mut self, touch: &Touch) {
+        *self = Self { barrel: self.barrel, eraser: self.eraser, ..Self::from_touch(touch) };
+    }
+}
+
+
+// maps pressure 0..1 onto 0..1
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PressureCurve {
+    #[default] Linear,
+    Gamma(f32), // < 1 softer, > 1 harder
+    Bezier([f32; 2], [f32; 2]), // cubic from (0, 0) to (1, 1) with two control points
+    Range { threshold: f32, min: f32, max: f32 }, // ignores pressure below threshold, output in min..max
+}
+
+impl PressureCurve {
+
+    pub fn apply(&self, pressure: f32) -> f32 {
+
+        let p = pressure.clamp(0.0, 1.0);
+
+        match *self {
+            Self::Linear => p,
+            Self::Gamma(gamma) => p.powf(gamma),
+            Self::Bezier(_, _) if p == 0.0 || p == 1.0 => p,
+            Self::Bezier(a, b) => {
+                let bezier = |t: f32, c1: f32, c2: f32| {
+                    let u = 1.0 - t;
+                    3.0*u*u*t*c1 + 3.0*u*t*t*c2 + t*t*t
+                };
+                // x is monotonic for control points within 0..1, bisect for t
+                let (mut lo, mut hi) = (0.0, 1.0);
+                for _ in 0..24 {
+                    let t = (lo + hi) / 2.0;
+                    if bezier(t, a[0], b[0]) < p { lo = t } else { hi = t }
+                }
+                bezier((lo + hi) / 2.0, a[1], b[1]).clamp(0.0, 1.0)
+            },
+            Self::Range { threshold, min, max } => {
+                if p < threshold { 0.0 }
+                else { min + (max - min) * (p - threshold) / (1.0 - threshold).max(f32::EPSILON) }
+            },
+        }
+    }
+}
+
+
+impl<T> TouchRegister<T> {
+
+    pub fn pen(&self) -> &PenState { &self.pen }
+    pub fn kind(&self) -> PointerKind { self.pen.kind }
+
+    // for platform specific sources of barrel button and eraser state
+    pub fn set_barrel(&mut self, pressed: bool) { self.pen.barrel = Some(pressed) }
+    pub fn set_eraser(&mut self, eraser: bool) { self.pen.eraser = Some(eraser) }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use winit::{event::{TouchPhase, DeviceId}, dpi::PhysicalPosition};
+
+    #[test]
+    fn pen_from_calibrated_force() {
+
+        let touch = |force| Touch { device_id: DeviceId::dummy(), phase: TouchPhase::Started, location: PhysicalPosition::new(0.0, 0.0), force, id: 0 };
+
+        let pen = PenState::from_touch(&touch(Some(Force::Calibrated { force: 1.0, max_possible_force: 4.0, altitude_angle: Some(FRAC_PI_2 as f64 / 3.0) })));
+
+        assert!(pen.is_pen());
+        assert_eq!(pen.calibrated_force(), Some(1.0));
+        assert!((pen.pressure.unwrap() - 0.5).abs() < 1e-5);
+        assert!((pen.tilt().unwrap() - FRAC_PI_2 * 2.0 / 3.0).abs() < 1e-5);
+
+        assert_eq!(PenState::from_touch(&touch(Some(Force::Normalized(0.3)))).kind, PointerKind::Finger);
+        assert_eq!(PenState::from_touch(&touch(None)).pressure, None);
+
+        for curve in [PressureCurve::Linear, PressureCurve::Gamma(2.0), PressureCurve::Bezier([0.2, 0.0], [0.8, 1.0])] {
+            assert_eq!(curve.apply(0.0), 0.0);
+            assert!((curve.apply(1.0) - 1.0).abs() < 1e-4);
+        }
+
+        assert!(PressureCurve::Bezier([0.0, 0.5], [0.5, 1.0]).apply(0.25) > 0.25);
+        assert_eq!(PressureCurve::Range { threshold: 0.2, min: 0.5, max: 1.0 }.apply(0.1), 0.0);
+        assert_eq!(PressureCurve::Range { threshold: 0.2, min: 0.5, max: 1.0 }.apply(0.6), 0.75);
+    }
+}
@@ -0,0 +1,338 @@
+use winit::event::{WindowEvent, Touch, TouchPhase, ElementState, MouseButton};
+use std::vec::Drain;
+use super::*;
+
+
+pub type PointerId = u64;
+
+// the mouse has a fixed id, touch contacts get increasing ids from 1 for as long as they last
+pub const MOUSE_POINTER_ID: PointerId = 0;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerPhase { Enter, Down, Move, Up, Cancel, Leave }
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PointerButtons(u32);
+
+impl PointerButtons {
+
+    fn bit(button: MouseButton) -> u32 {
+        match button {
+            MouseButton::Left => 1,
+            MouseButton::Right => 1 << 1,
+            MouseButton::Middle => 1 << 2,
+            MouseButton::Back => 1 << 3,
+            MouseButton::Forward => 1 << 4,
+            MouseButton::Other(n) => 1 << (5 + n.min(26)),
+        }
+    }
+
+    pub fn contains(&self, button: MouseButton) -> bool { self.0 & Self::bit(button) != 0 }
+    pub fn is_empty(&self) -> bool { self.0 == 0 }
+
+    pub fn insert(&mut self, button: MouseButton) { self.0 |= Self::bit(button) }
+    pub fn remove(&mut self, button: MouseButton) { self.0 &= !Self::bit(button) }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerEvent {
+    pub id: PointerId,
+    pub kind: PointerKind,
+    pub phase: PointerPhase,
+    pub position: TouchPos, // in the coordinates of the pointers touches
+    pub button: Option<MouseButton>, // which changed, touches and pens act as the left button
+    pub buttons: PointerButtons, // held after the event
+    pub pressure: f32, // 0.5 for pressed pointers without pressure support, as in the dom
+    pub pen: Option<PenState>,
+    pub primary: bool,
+}
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PointerTouch { pub pointer_id: PointerId }
+
+impl TouchExt for PointerTouch {
+    fn new(_id: u64, _location: TouchPos, _force: Option<f32>) -> Self { Self::default() }
+}
+
+
+// builds pointer events from mouse and touch window events
+
+#[derive(Debug, Clone)]
+pub struct Pointers {
+    touches: Touches<PointerTouch>,
+    mouse: Option<TouchPos>,
+    mouse_outside: bool, // left while buttons are held, the drag continues outside
+    buttons: PointerButtons,
+    next_id: PointerId,
+    primary_touch: Option<PointerId>,
+    events: Vec<PointerEvent>,
+}
+
+impl Default for Pointers {
+    fn default() -> Self { Self::new() }
+}
+
+impl Pointers {
+
+    pub fn new() -> Self {
+        Self {
+            touches: Touches::new(), mouse: None, mouse_outside: false, buttons: PointerButtons::default(),
+            next_id: MOUSE_POINTER_ID + 1, primary_touch: None, events: Vec::new(),
+        }
+    }
+
+    pub fn with_coords(mut self, coords: TouchCoords) -> Self { self.touches.set_coords(coords); self }
+
+    pub fn touches(&self) -> &Touches<PointerTouch> { &self.touches }
+
+    pub fn mouse_position(&self) -> Option<TouchPos> { self.mouse }
+    pub fn mouse_buttons(&self) -> PointerButtons { self.buttons }
+
+    pub fn position(&self, id: PointerId) -> Option<TouchPos> {
+        if id == MOUSE_POINTER_ID { self.mouse }
+        else { self.touches.iter().find(|reg| reg.ext.pointer_id == id).map(|reg| reg.location()) }
+    }
+
+    // ids of all pointers over the window, including the mouse
+    pub fn active(&self) -> impl Iterator<Item=PointerId> + '_ {
+        self.mouse.map(|_| MOUSE_POINTER_ID).into_iter().chain(self.touches.iter().map(|reg| reg.ext.pointer_id))
+    }
+
+    pub fn update(&mut self, event: &WindowEvent) -> Drain<'_, PointerEvent> {
+
+        // updates the touch registers and the coordinates
+        let coords = *self.touches.coords();
+        self.touches.window_event(event);
+        self.mouse = self.mouse.map(|pos| self.touches.coords().convert_from(&coords, pos));
+
+        match event {
+
+            WindowEvent::CursorEntered { .. } => self.mouse_outside = false, // the position is known with the first move
+
+            WindowEvent::CursorMoved { position, .. } => {
+                let position = self.touches.coords().from_physical(*position);
+                let entered = self.mouse.is_none();
+                self.mouse = Some(position);
+                if entered { self.mouse_event(PointerPhase::Enter, None) }
+                self.mouse_event(PointerPhase::Move, None);
+            },
+
+            // with held buttons, moves and the release still arrive while the mouse is captured
+            WindowEvent::CursorLeft { .. } if self.mouse.is_some() => {
+                if self.buttons.is_empty() { self.mouse_leave() }
+                else { self.mouse_outside = true }
+            },
+
+            WindowEvent::MouseInput { state, button, .. } if self.mouse.is_some() => {
+                let was_empty = self.buttons.is_empty();
+                match state {
+                    ElementState::Pressed => self.buttons.insert(*button),
+                    ElementState::Released => self.buttons.remove(*button),
+                }
+                // chorded buttons change with moves
+                let phase = match state {
+                    ElementState::Pressed if was_empty => PointerPhase::Down,
+                    ElementState::Released if self.buttons.is_empty() => PointerPhase::Up,
+                    _ => PointerPhase::Move,
+                };
+                self.mouse_event(phase, Some(*button));
+                if self.mouse_outside && self.buttons.is_empty() { self.mouse_leave() }
+            },
+
+            WindowEvent::Touch(touch) => self.touch_event(touch),
+
+            WindowEvent::Focused(false) => {
+                let active: Vec<_> = self.touches.iter().filter(|reg| !reg.ended()).copied().collect();
+                for reg in active {
+                    self.touch_pointer_event(&reg, PointerPhase::Cancel);
+                    self.touch_pointer_event(&reg, PointerPhase::Leave);
+                }
+                self.touches.clear(|_| true);
+                self.primary_touch = None;
+                if !self.buttons.is_empty() {
+                    self.buttons = PointerButtons::default();
+                    self.mouse_event(PointerPhase::Cancel, None);
+                }
+                if self.mouse_outside { self.mouse_leave() }
+            },
+
+            _ => {},
+        }
+
+        self.events.drain(..)
+    }
+
+
+    fn mouse_leave(&mut self) {
+        self.mouse_event(PointerPhase::Leave, None);
+        self.mouse = None;
+        self.mouse_outside = false;
+    }
+
+    fn mouse_event(&mut self, phase: PointerPhase, button: Option<MouseButton>) {
+        if let Some(position) = self.mouse {
+            self.events.push(PointerEvent {
+                id: MOUSE_POINTER_ID, kind: PointerKind::Mouse, phase, position, button,
+                buttons: self.buttons,
+                pressure: if self.buttons.is_empty() { 0.0 } else { 0.5 },
+                pen: None, primary: true,
+            });
+        }
+    }
+
+    // the registers are already updated
+    fn touch_event(&mut self, touch: &Touch) {
+
+        let Some(reg) = self.touches.iter_mut().rev().find(|reg| reg.id() == touch.id) else { return };
+
+        if touch.phase == TouchPhase::Started {
+            reg.ext.pointer_id = self.next_id;
+            self.next_id += 1;
+            if self.primary_touch.is_none() { self.primary_touch = Some(reg.ext.pointer_id) }
+        }
+
+        let reg = *reg;
+
+        match touch.phase {
+            TouchPhase::Started => {
+                self.touch_pointer_event(&reg, PointerPhase::Enter);
+                self.touch_pointer_event(&reg, PointerPhase::Down);
+            },
+            TouchPhase::Moved => self.touch_pointer_event(&reg, PointerPhase::Move),
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                let phase = if touch.phase == TouchPhase::Ended { PointerPhase::Up } else { PointerPhase::Cancel };
+                self.touch_pointer_event(&reg, phase);
+                self.touch_pointer_event(&reg, PointerPhase::Leave);
+            },
+        }
+
+        self.touches.clear(|reg| reg.ended());
+
+        if self.touches.is_empty() { self.primary_touch = None }
+    }
+
+    fn touch_pointer_event(&mut self, reg: &TouchRegister<PointerTouch>, phase: PointerPhase) {
+
+        let pressed = matches!(phase, PointerPhase::Down | PointerPhase::Move);
+
+        let mut buttons = PointerButtons::default();
+        if pressed { buttons.insert(MouseButton::Left) }
+
+        let pen = reg.pen();
+
+        self.events.push(PointerEvent {
+            id: reg.ext.pointer_id,
+            kind: if pen.kind == PointerKind::Unknown { PointerKind::Finger } else { pen.kind },
+            phase, position: reg.location(),
+            button: matches!(phase, PointerPhase::Down | PointerPhase::Up).then_some(MouseButton::Left),
+            buttons,
+            pressure: if pressed { pen.pressure.unwrap_or(0.5) } else { 0.0 },
+            pen: pen.is_pen().then_some(*pen),
+            primary: self.primary_touch == Some(reg.ext.pointer_id),
+        });
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use winit::{event::DeviceId, dpi::{PhysicalPosition, PhysicalSize}};
+
+    const DEVICE_ID: DeviceId = DeviceId::dummy();
+
+    fn phases(events: Drain<'_, PointerEvent>) -> Vec<(PointerId, PointerPhase)> {
+        events.map(|event| (event.id, event.phase)).collect()
+    }
+
+    #[test]
+    fn mouse_and_touch_pointers() {
+
+        use PointerPhase::*;
+
+        let mut pointers = Pointers::new();
+
+        let moved = WindowEvent::CursorMoved { device_id: DEVICE_ID, position: PhysicalPosition::new(5.0, 5.0) };
+        let button = |state, button| WindowEvent::MouseInput { device_id: DEVICE_ID, state, button };
+        let touch = |phase, id| WindowEvent::Touch(Touch { device_id: DEVICE_ID, phase, location: PhysicalPosition::new(1.0, 2.0), force: None, id });
+
+        assert_eq!(phases(pointers.update(&moved)), [(0, Enter), (0, Move)]);
+        assert_eq!(phases(pointers.update(&button(ElementState::Pressed, MouseButton::Left))), [(0, Down)]);
+        assert_eq!(phases(pointers.update(&button(ElementState::Pressed, MouseButton::Right))), [(0, Move)]);
+        assert_eq!(phases(pointers.update(&button(ElementState::Released, MouseButton::Left))), [(0, Move)]);
+        assert_eq!(phases(pointers.update(&button(ElementState::Released, MouseButton::Right))), [(0, Up)]);
+
+        // touch ids are reused by the platform, pointer ids are not
+        assert_eq!(phases(pointers.update(&touch(TouchPhase::Started, 7))), [(1, Enter), (1, Down)]);
+        assert_eq!(phases(pointers.update(&touch(TouchPhase::Started, 8))), [(2, Enter), (2, Down)]);
+        assert_eq!(pointers.active().collect::<Vec<_>>(), [0, 1, 2]);
+
+        let events: Vec<_> = pointers.update(&touch(TouchPhase::Ended, 7)).collect();
+        assert_eq!(events.iter().map(|event| (event.id, event.phase)).collect::<Vec<_>>(), [(1, Up), (1, Leave)]);
+        assert!(events[0].primary && events[0].kind == PointerKind::Finger);
+
+        assert_eq!(phases(pointers.update(&touch(TouchPhase::Cancelled, 8))), [(2, Cancel), (2, Leave)]);
+        assert_eq!(phases(pointers.update(&touch(TouchPhase::Started, 7))), [(3, Enter), (3, Down)]);
+        assert_eq!(pointers.position(3), Some(TouchPos::new(1.0, 2.0)));
+    }
+
+    #[test]
+    fn mouse_capture() {
+
+        use PointerPhase::*;
+
+        let mut pointers = Pointers::new();
+
+        let moved = WindowEvent::CursorMoved { device_id: DEVICE_ID, position: PhysicalPosition::new(5.0, 5.0) };
+        let pressed = WindowEvent::MouseInput { device_id: DEVICE_ID, state: ElementState::Pressed, button: MouseButton::Left };
+        let released = WindowEvent::MouseInput { device_id: DEVICE_ID, state: ElementState::Released, button: MouseButton::Left };
+        let left = WindowEvent::CursorLeft { device_id: DEVICE_ID };
+
+        // dragged and released outside of the window
+        pointers.update(&moved).for_each(drop);
+        pointers.update(&pressed).for_each(drop);
+        assert!(phases(pointers.update(&left)).is_empty());
+        assert_eq!(phases(pointers.update(&moved)), [(0, Move)]);
+        assert_eq!(phases(pointers.update(&released)), [(0, Up), (0, Leave)]);
+        assert_eq!(pointers.mouse_position(), None);
+
+        // back inside before the release
+        assert_eq!(phases(pointers.update(&moved)), [(0, Enter), (0, Move)]);
+        pointers.update(&pressed).for_each(drop);
+        pointers.update(&left).for_each(drop);
+        pointers.update(&WindowEvent::CursorEntered { device_id: DEVICE_ID }).for_each(drop);
+        assert_eq!(phases(pointers.update(&released)), [(0, Up)]);
+
+        // focus lost with held buttons
+        assert_eq!(phases(pointers.update(&pressed)), [(0, Down)]);
+        assert_eq!(phases(pointers.update(&WindowEvent::Focused(false))), [(0, Cancel)]);
+        assert!(pointers.mouse_buttons().is_empty());
+        assert_eq!(phases(pointers.update(&pressed)), [(0, Down)]);
+
+        pointers.update(&left).for_each(drop);
+        assert_eq!(phases(pointers.update(&WindowEvent::Focused(false))), [(0, Cancel), (0, Leave)]);
+    }
+
+    #[test]
+    fn positions_follow_window() {
+
+        let mut pointers = Pointers::new().with_coords(TouchCoords::new(TouchSpace::Normalized));
+
+        let moved = WindowEvent::CursorMoved { device_id: DEVICE_ID, position: PhysicalPosition::new(50.0, 50.0) };
+        let touch = WindowEvent::Touch(Touch { device_id: DEVICE_ID, phase: TouchPhase::Started, location: PhysicalPosition::new(100.0, 25.0), force: None, id: 0 });
+
+        pointers.update(&WindowEvent::Resized(PhysicalSize::new(200, 100))).for_each(drop);
+        assert_eq!(pointers.update(&moved).map(|event| event.position).collect::<Vec<_>>(), [TouchPos::new(0.25, 0.5); 2]);
+        assert_eq!(pointers.update(&touch).map(|event| event.position).collect::<Vec<_>>(), [TouchPos::new(0.5, 0.25); 2]);
+
+        pointers.update(&WindowEvent::Resized(PhysicalSize::new(100, 100))).for_each(drop);
+        assert_eq!(pointers.mouse_position(), Some(TouchPos::new(0.5, 0.5)));
+        assert_eq!(pointers.position(1), Some(TouchPos::new(1.0, 0.25)));
+    }
+}
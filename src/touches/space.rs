@@ -0,0 +1,147 @@
+use winit::{event::WindowEvent, dpi::{PhysicalPosition, PhysicalSize}, window::Window};
+use super::*;
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TouchSpace {
+    #[default] Physical,
+    Logical, // physical divided by the scale factor
+    Normalized, // 0..1 across the window size on both axes
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchCoords {
+    pub space: TouchSpace,
+    pub scale_factor: f64,
+    pub window_size: PhysicalSize<u32>,
+}
+
+impl Default for TouchCoords {
+    fn default() -> Self { Self { space: TouchSpace::Physical, scale_factor: 1.0, window_size: PhysicalSize::new(1, 1) } }
+}
+
+impl TouchCoords {
+
+    pub fn new(space: TouchSpace) -> Self { Self { space, ..Default::default() } }
+
+    pub fn for_window(space: TouchSpace, window: &Window) -> Self {
+        Self { space, scale_factor: window.scale_factor(), window_size: window.inner_size() }
+    }
+
+    fn factors(&self) -> [f64; 2] {
+        match self.space {
+            TouchSpace::Physical => [1.0, 1.0],
+            TouchSpace::Logical => [self.scale_factor, self.scale_factor],
+            TouchSpace::Normalized => [self.window_size.width.max(1) as f64, self.window_size.height.max(1) as f64],
+        }
+    }
+
+    pub fn from_physical(&self, pos: PhysicalPosition<f64>) -> TouchPos {
+        let [fx, fy] = self.factors();
+        TouchPos::new((pos.x / fx) as f32, (pos.y / fy) as f32)
+    }
+
+    pub fn to_physical(&self, pos: TouchPos) -> PhysicalPosition<f64> {
+        let [fx, fy] = self.factors();
+        PhysicalPosition::new(pos.x as f64 * fx, pos.y as f64 * fy)
+    }
+
+    // touch units per logical pixel, the mean of both axes for normalized coordinates
+    pub fn logical_scale(&self) -> f64 {
+        let [fx, fy] = self.factors();
+        self.scale_factor * 2.0 / (fx + fy)
+    }
+
+    // re-express a position given in other coordinates
+    pub fn convert_from(&self, other: &Self, pos: TouchPos) -> TouchPos {
+        self.from_physical(other.to_physical(pos))
+    }
+}
+
+
+impl<T: TouchExt + 'static, const SHRINK_TO:usize, const MAX:usize> Touches<T, SHRINK_TO, MAX> {
+
+    pub fn with_coords(mut self, coords: TouchCoords) -> Self { self.set_coords(coords); self }
+
+    pub fn coords(&self) -> &TouchCoords { &self.coords }
+
+    // converts existing registers into the new coordinates
+    pub fn set_coords(&mut self, coords: TouchCoords) {
+
+        let old = std::mem::replace(&mut self.coords, coords);
+
+        if old.factors() != coords.factors() {
+            for reg in self.vec.iter_mut() {
+                reg.location = coords.convert_from(&old, reg.location);
+                reg.ref_location = coords.convert_from(&old, reg.ref_location);
+                reg.samples.map_locations(|pos| coords.convert_from(&old, pos));
+            }
+        }
+    }
+
+    pub fn set_space(&mut self, space: TouchSpace) { self.set_coords(TouchCoords { space, ..self.coords }) }
+    pub fn set_scale_factor(&mut self, scale_factor: f64) { self.set_coords(TouchCoords { scale_factor, ..self.coords }) }
+    pub fn set_window_size(&mut self, window_size: PhysicalSize<u32>) { self.set_coords(TouchCoords { window_size, ..self.coords }) }
+
+    // single feed for window events, updates the registers with touches
+    // and keeps scale factor and window size up to date
+    pub fn window_event(&mut self, event: &WindowEvent) { self.window_event_at(event, Instant::now()) }
+
+    pub fn window_event_at(&mut self, event: &WindowEvent, instant: Instant) {
+        match event {
+            WindowEvent::Touch(touch) => self.update_at(*touch, instant),
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => self.set_scale_factor(*scale_factor),
+            WindowEvent::Resized(size) => self.set_window_size(*size),
+            _ => {},
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use winit::event::{Touch, TouchPhase, DeviceId};
+
+    #[test]
+    fn converts_between_spaces() {
+
+        let mut touches = Touches::<()>::new().with_coords(TouchCoords {
+            space: TouchSpace::Logical, scale_factor: 2.0, window_size: PhysicalSize::new(400, 200),
+        });
+
+        for (id, x) in [(0, 100.0), (1, 300.0)] {
+            touches.update(Touch { device_id: DeviceId::dummy(), phase: TouchPhase::Started, location: PhysicalPosition::new(x, 100.0), force: None, id });
+        }
+
+        assert_eq!(touches.iter().map(|reg| reg.location()).collect::<Vec<_>>(), [TouchPos::new(50.0, 50.0), TouchPos::new(150.0, 50.0)]);
+        assert_eq!(TouchRegister::spread(touches.iter()), 100.0);
+
+        touches.window_event(&WindowEvent::Resized(PhysicalSize::new(400, 400))); // no effect in logical space
+        touches.set_space(TouchSpace::Normalized);
+
+        assert_eq!(touches.iter().map(|reg| reg.location()).collect::<Vec<_>>(), [TouchPos::new(0.25, 0.25), TouchPos::new(0.75, 0.25)]);
+        assert_eq!(touches.iter().map(|reg| reg.ref_location).collect::<Vec<_>>(), [TouchPos::new(0.25, 0.25), TouchPos::new(0.75, 0.25)]);
+    }
+
+    #[test]
+    fn follows_window_events() {
+
+        let mut touches = Touches::<()>::new().with_coords(TouchCoords::new(TouchSpace::Normalized));
+
+        let touch = |phase, x, y| WindowEvent::Touch(Touch { device_id: DeviceId::dummy(), phase, location: PhysicalPosition::new(x, y), force: None, id: 0 });
+
+        touches.window_event(&WindowEvent::Resized(PhysicalSize::new(400, 200)));
+        touches.window_event(&touch(TouchPhase::Started, 100.0, 50.0));
+        assert_eq!(touches.iter().map(|reg| reg.location()).collect::<Vec<_>>(), [TouchPos::new(0.25, 0.25)]);
+
+        touches.window_event(&WindowEvent::Resized(PhysicalSize::new(200, 200)));
+        assert_eq!(touches.iter().map(|reg| reg.location()).collect::<Vec<_>>(), [TouchPos::new(0.5, 0.25)]);
+
+        touches.window_event(&touch(TouchPhase::Moved, 100.0, 100.0));
+        assert_eq!(touches.iter().map(|reg| (reg.location(), reg.delta_location())).collect::<Vec<_>>(), [(TouchPos::new(0.5, 0.5), TouchPos::new(0.0, 0.25))]);
+    }
+}
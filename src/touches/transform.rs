@@ -0,0 +1,193 @@
+use super::*;
+
+
+// similarity transform: p' = pivot + translation + rotate(scale * (p - pivot))
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimilarityTransform {
+    pub translation: TouchPos,
+    pub scale: f32,
+    pub rotation: f32,
+    pub pivot: TouchPos,
+}
+
+impl Default for SimilarityTransform {
+    fn default() -> Self { Self::IDENTITY }
+}
+
+impl SimilarityTransform {
+
+    pub const IDENTITY: Self = Self { translation: TouchPos::ZERO, scale: 1.0, rotation: 0.0, pivot: TouchPos::ZERO };
+
+    pub fn is_identity(&self) -> bool {
+        self.translation == TouchPos::ZERO && self.scale == 1.0 && self.rotation == 0.0
+    }
+
+    pub fn apply(&self, pos: TouchPos) -> TouchPos {
+        self.pivot + self.translation + TouchPos::from_angle(self.rotation).rotate((pos - self.pivot) * self.scale)
+    }
+
+    pub fn to_affine(&self) -> glam::Affine2 {
+        glam::Affine2::from_translation(self.pivot + self.translation) *
+        glam::Affine2::from_scale_angle_translation(TouchPos::splat(self.scale), self.rotation, TouchPos::ZERO) *
+        glam::Affine2::from_translation(-self.pivot)
+    }
+
+    pub fn constrained(mut self, constraints: &TransformConstraints) -> Self {
+        if !constraints.translate_x { self.translation.x = 0.0 }
+        if !constraints.translate_y { self.translation.y = 0.0 }
+        if !constraints.scale { self.scale = 1.0 }
+        if !constraints.rotate { self.rotation = 0.0 }
+        self
+    }
+
+    // least-squares fit of (reference, current) location pairs,
+    // exact for two pairs, translation only for a single pair
+    pub fn solve(pairs: impl IntoIterator<Item=(TouchPos, TouchPos), IntoIter: Clone>) -> Self {
+
+        let pairs = pairs.into_iter();
+
+        let (mut n, mut ref_sum, mut sum) = (0, TouchPos::ZERO, TouchPos::ZERO);
+
+        for (ref_pos, pos) in pairs.clone() {
+            n += 1;
+            ref_sum += ref_pos;
+            sum += pos;
+        }
+
+        if n == 0 { return Self::IDENTITY }
+
+        let pivot = ref_sum / n as f32;
+        let translation = sum / n as f32 - pivot;
+
+        if n == 1 { return Self { pivot, translation, ..Self::IDENTITY } }
+
+        let center = sum / n as f32;
+
+        // covariance terms
+        let (mut dot, mut cross, mut ref_var) = (0.0, 0.0, 0.0);
+
+        for (ref_pos, pos) in pairs {
+            let (a, b) = (ref_pos - pivot, pos - center);
+            dot += a.dot(b);
+            cross += a.perp_dot(b);
+            ref_var += a.length_squared();
+        }
+
+        if ref_var <= f32::EPSILON { return Self { pivot, translation, ..Self::IDENTITY } }
+
+        Self {
+            translation, pivot,
+            scale: (dot * dot + cross * cross).sqrt() / ref_var,
+            rotation: cross.atan2(dot),
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransformConstraints {
+    pub translate_x: bool,
+    pub translate_y: bool,
+    pub scale: bool,
+    pub rotate: bool,
+}
+
+impl Default for TransformConstraints {
+    fn default() -> Self { Self { translate_x: true, translate_y: true, scale: true, rotate: true } }
+}
+
+
+// exponential smoothing of successive incremental transforms, factor in (0, 1], 1 is unsmoothed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformSmoother {
+    pub factor: f32,
+    last: Option<SimilarityTransform>,
+}
+
+impl TransformSmoother {
+
+    pub fn new(factor: f32) -> Self { Self { factor, last: None } }
+
+    pub fn reset(&mut self) { self.last = None }
+
+    pub fn smooth(&mut self, transform: SimilarityTransform) -> SimilarityTransform {
+
+        let smoothed = match self.last {
+            None => transform,
+            Some(last) => SimilarityTransform {
+                translation: last.translation.lerp(transform.translation, self.factor),
+                scale: (last.scale.ln() + (transform.scale.ln() - last.scale.ln()) * self.factor).exp(),
+                rotation: last.rotation + (transform.rotation - last.rotation).angle_as_delta() * self.factor,
+                pivot: transform.pivot,
+            },
+        };
+
+        self.last = Some(smoothed);
+        smoothed
+    }
+}
+
+
+impl<T> TouchRegister<T> {
+    pub fn transform<'a>(items: impl IntoIterator<Item=&'a Self, IntoIter: Clone>) -> SimilarityTransform {
+        SimilarityTransform::solve(items.into_iter().map(|reg| (reg.ref_location, reg.location)))
+    }
+}
+
+impl<T: TouchExt + 'static, const SHRINK_TO:usize, const MAX:usize> Touches<T, SHRINK_TO, MAX> {
+
+    // transform of the active touches since the last reset of deltas
+    pub fn transform(&self) -> SimilarityTransform {
+        TouchRegister::transform(self.iter().filter(|reg| !reg.ended))
+    }
+
+    pub fn constrained_transform(&self, constraints: &TransformConstraints) -> SimilarityTransform {
+        self.transform().constrained(constraints)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn assert_close(a: TouchPos, b: TouchPos) {
+        assert!(a.distance(b) < 1e-3, "{a} != {b}");
+    }
+
+    #[test]
+    fn recovers_known_transform() {
+
+        let known = SimilarityTransform {
+            translation: TouchPos::new(5.0, -3.0), scale: 1.5, rotation: 0.4, pivot: TouchPos::ZERO,
+        };
+
+        for points in [
+            &[TouchPos::new(10.0, 10.0), TouchPos::new(30.0, 20.0)][..],
+            &[TouchPos::new(10.0, 10.0), TouchPos::new(30.0, 20.0), TouchPos::new(-5.0, 40.0), TouchPos::new(0.0, 0.0)][..],
+        ] {
+            let solved = SimilarityTransform::solve(points.iter().map(|p| (*p, known.apply(*p))));
+
+            assert!((solved.scale - 1.5).abs() < 1e-4);
+            assert!((solved.rotation - 0.4).abs() < 1e-4);
+
+            for p in points {
+                assert_close(solved.apply(*p), known.apply(*p));
+                assert_close(solved.to_affine().transform_point2(*p), known.apply(*p));
+            }
+        }
+    }
+
+    #[test]
+    fn single_and_constrained() {
+
+        let solved = SimilarityTransform::solve([(TouchPos::new(1.0, 1.0), TouchPos::new(4.0, 5.0))]);
+        assert_eq!(solved, SimilarityTransform { translation: TouchPos::new(3.0, 4.0), pivot: TouchPos::new(1.0, 1.0), ..SimilarityTransform::IDENTITY });
+
+        let constrained = solved.constrained(&TransformConstraints { translate_y: false, ..Default::default() });
+        assert_eq!(constrained.translation, TouchPos::new(3.0, 0.0));
+    }
+}
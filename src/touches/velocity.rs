@@ -0,0 +1,170 @@
+use crate::time::{Instant, Duration};
+use super::*;
+
+
+pub const TOUCH_SAMPLES: usize = 16;
+
+pub const VELOCITY_WINDOW: Duration = Duration::from_millis(100);
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchSample {
+    pub location: TouchPos,
+    pub instant: Instant,
+}
+
+
+// bounded history of locations, newest last
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchSamples {
+    buffer: [Option<TouchSample>; TOUCH_SAMPLES],
+    next: usize,
+}
+
+impl TouchSamples {
+
+    pub fn new(sample: TouchSample) -> Self {
+        let mut samples = Self { buffer: [None; TOUCH_SAMPLES], next: 0 };
+        samples.push(sample);
+        samples
+    }
+
+    pub fn push(&mut self, sample: TouchSample) {
+        self.buffer[self.next] = Some(sample);
+        self.next = (self.next + 1) % TOUCH_SAMPLES;
+    }
+
+    pub fn newest(&self) -> Option<TouchSample> {
+        self.buffer[(self.next + TOUCH_SAMPLES - 1) % TOUCH_SAMPLES]
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item=TouchSample> + Clone + '_ {
+        self.buffer[self.next..].iter().chain(self.buffer[..self.next].iter()).flatten().copied()
+    }
+
+    pub fn map_locations(&mut self, mut func: impl FnMut(TouchPos) -> TouchPos) {
+        self.buffer.iter_mut().flatten().for_each(|sample| sample.location = func(sample.location));
+    }
+
+    // least-squares fit over the samples within window before the newest one, per second
+    pub fn velocity(&self, window: Duration) -> TouchPos {
+
+        let Some(newest) = self.newest() else { return TouchPos::ZERO };
+
+        let samples = self.iter().rev().take_while(|sample| newest.instant - sample.instant <= window);
+
+        let time = |sample: &TouchSample| -(newest.instant - sample.instant).as_secs_f32();
+
+        let (mut n, mut t_sum, mut pos_sum) = (0, 0.0, TouchPos::ZERO);
+
+        for sample in samples.clone() {
+            n += 1;
+            t_sum += time(&sample);
+            pos_sum += sample.location;
+        }
+
+        if n < 2 { return TouchPos::ZERO }
+
+        let (t_mean, pos_mean) = (t_sum / n as f32, pos_sum / n as f32);
+        let (mut t_var, mut covar) = (0.0, TouchPos::ZERO);
+
+        for sample in samples {
+            let dt = time(&sample) - t_mean;
+            t_var += dt * dt;
+            covar += (sample.location - pos_mean) * dt;
+        }
+
+        if t_var <= f32::EPSILON { TouchPos::ZERO } else { covar / t_var }
+    }
+}
+
+
+impl<T> TouchRegister<T> {
+
+    pub fn samples(&self) -> &TouchSamples { &self.samples }
+
+    pub fn velocity(&self) -> TouchPos { self.samples.velocity(VELOCITY_WINDOW) }
+
+    pub fn velocity_within(&self, window: Duration) -> TouchPos { self.samples.velocity(window) }
+
+    pub fn mean_velocity<'a>(items: impl IntoIterator<Item=&'a Self>) -> TouchPos {
+        TouchPos::mean(items.into_iter().map(|reg| reg.velocity()))
+    }
+}
+
+impl<T: TouchExt + 'static, const SHRINK_TO:usize, const MAX:usize> Touches<T, SHRINK_TO, MAX> {
+    // mean over all registers, including ended ones until they are cleared
+    pub fn velocity(&self) -> TouchPos { TouchRegister::mean_velocity(self.iter()) }
+}
+
+
+// kinetic continuation with exponential decay
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Inertia {
+    pub velocity: TouchPos,
+    pub decay: f32, // rate per second
+    pub min_speed: f32, // stops below
+    instant: Instant,
+}
+
+impl Inertia {
+
+    pub fn new(velocity: TouchPos, instant: Instant) -> Self {
+        Self { velocity, decay: 4.0, min_speed: 10.0, instant }
+    }
+
+    pub fn with_decay(mut self, decay: f32) -> Self { self.decay = decay; self }
+    pub fn with_min_speed(mut self, min_speed: f32) -> Self { self.min_speed = min_speed; self }
+
+    pub fn is_done(&self) -> bool { self.velocity == TouchPos::ZERO }
+
+    // returns the distance travelled since the last advance
+    pub fn advance(&mut self, instant: Instant) -> TouchPos {
+
+        if instant <= self.instant || self.is_done() { return TouchPos::ZERO }
+
+        let dt = (instant - self.instant).as_secs_f32();
+        self.instant = instant;
+
+        let factor = (-self.decay * dt).exp();
+
+        let delta = if self.decay > 0.0 { self.velocity * (1.0 - factor) / self.decay } else { self.velocity * dt };
+
+        self.velocity *= factor;
+        if self.velocity.length() < self.min_speed { self.velocity = TouchPos::ZERO }
+
+        delta
+    }
+
+    // total distance until the velocity decays to zero
+    pub fn remaining_distance(&self) -> TouchPos {
+        if self.decay > 0.0 { self.velocity / self.decay } else { TouchPos::ZERO }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn velocity_and_inertia() {
+
+        let start = Instant::now();
+        let at = |ms: u64, x: f32| TouchSample { location: TouchPos::new(x, 0.0), instant: start + Duration::from_millis(ms) };
+
+        let mut samples = TouchSamples::new(at(0, 500.0)); // outside of the window
+        for i in 0..30 { samples.push(at(200 + i * 10, i as f32 * 5.0)) }
+
+        assert_eq!(samples.iter().count(), TOUCH_SAMPLES);
+        assert!((samples.velocity(VELOCITY_WINDOW) - TouchPos::new(500.0, 0.0)).length() < 0.5);
+
+        let mut inertia = Inertia::new(TouchPos::new(500.0, 0.0), start);
+        let total = (1..=200).map(|i| inertia.advance(start + Duration::from_millis(i * 16))).sum::<TouchPos>();
+
+        assert!(inertia.is_done());
+        assert!(total.x > 120.0 && total.x <= 125.0);
+    }
+}
@@ -0,0 +1,363 @@
+use crate::time::{Instant, Duration};
+use std::f32::consts::PI;
+
+
+// values that can be interpolated and simulated
+
+pub trait Animatable: Copy {
+    fn sum(self, other: Self) -> Self;
+    fn scale(self, factor: f32) -> Self;
+    fn magnitude(self) -> f32;
+
+    fn diff(self, other: Self) -> Self { self.sum(other.scale(-1.0)) }
+    fn interpolate(self, other: Self, t: f32) -> Self { self.sum(other.diff(self).scale(t)) }
+}
+
+impl Animatable for f32 {
+    fn sum(self, other: Self) -> Self { self + other }
+    fn scale(self, factor: f32) -> Self { self * factor }
+    fn magnitude(self) -> f32 { self.abs() }
+}
+
+impl Animatable for f64 {
+    fn sum(self, other: Self) -> Self { self + other }
+    fn scale(self, factor: f32) -> Self { self * factor as f64 }
+    fn magnitude(self) -> f32 { self.abs() as f32 }
+}
+
+impl<const N: usize> Animatable for [f32; N] {
+    fn sum(self, other: Self) -> Self { std::array::from_fn(|i| self[i] + other[i]) }
+    fn scale(self, factor: f32) -> Self { self.map(|v| v * factor) }
+    fn magnitude(self) -> f32 { self.iter().map(|v| v * v).sum::<f32>().sqrt() }
+}
+
+#[cfg(feature = "touches")]
+macro_rules! impl_animatable_glam {
+    ($($type:ty),*) => {$(
+        impl Animatable for $type {
+            fn sum(self, other: Self) -> Self { self + other }
+            fn scale(self, factor: f32) -> Self { self * factor }
+            fn magnitude(self) -> f32 { self.length() }
+        }
+    )*};
+}
+
+#[cfg(feature = "touches")]
+impl_animatable_glam!(glam::Vec2, glam::Vec3, glam::Vec3A, glam::Vec4);
+
+
+// easing curves, mapping 0..=1 to 0..=1 with possible over- and undershoot
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Easing {
+    #[default] Linear,
+    QuadIn, QuadOut, QuadInOut,
+    CubicIn, CubicOut, CubicInOut,
+    SineIn, SineOut, SineInOut,
+    ExpoIn, ExpoOut, ExpoInOut,
+    BackIn, BackOut, BackInOut,
+    ElasticOut,
+    BounceOut,
+    Steps(u32),
+    CubicBezier(f32, f32, f32, f32), // control points as in css
+}
+
+impl Easing {
+
+    pub fn apply(&self, t: f32) -> f32 {
+
+        let t = t.clamp(0.0, 1.0);
+
+        // in-out from an in-curve
+        let in_out = |ease_in: fn(f32) -> f32| {
+            if t < 0.5 { ease_in(2.0 * t) / 2.0 } else { 1.0 - ease_in(2.0 - 2.0 * t) / 2.0 }
+        };
+
+        const BACK: f32 = 1.70158;
+
+        fn cubic(t: f32) -> f32 { t * t * t }
+        fn sine(t: f32) -> f32 { 1.0 - (t * PI / 2.0).cos() }
+        fn expo(t: f32) -> f32 { if t == 0.0 { 0.0 } else { 2f32.powf(10.0 * t - 10.0) } }
+        fn back(t: f32) -> f32 { t * t * ((BACK + 1.0) * t - BACK) }
+
+        fn bounce(t: f32) -> f32 {
+            const N: f32 = 7.5625;
+            const D: f32 = 2.75;
+            if t < 1.0 / D { N * t * t }
+            else if t < 2.0 / D { let t = t - 1.5 / D; N * t * t + 0.75 }
+            else if t < 2.5 / D { let t = t - 2.25 / D; N * t * t + 0.9375 }
+            else { let t = t - 2.625 / D; N * t * t + 0.984375 }
+        }
+
+        match *self {
+            Self::Linear => t,
+            Self::QuadIn => t * t,
+            Self::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Self::QuadInOut => in_out(|t| t * t),
+            Self::CubicIn => cubic(t),
+            Self::CubicOut => 1.0 - cubic(1.0 - t),
+            Self::CubicInOut => in_out(cubic),
+            Self::SineIn => sine(t),
+            Self::SineOut => 1.0 - sine(1.0 - t),
+            Self::SineInOut => in_out(sine),
+            Self::ExpoIn => expo(t),
+            Self::ExpoOut => 1.0 - expo(1.0 - t),
+            Self::ExpoInOut => in_out(expo),
+            Self::BackIn => back(t),
+            Self::BackOut => 1.0 - back(1.0 - t),
+            Self::BackInOut => in_out(back),
+            Self::ElasticOut => {
+                if t == 0.0 || t == 1.0 { t }
+                else { 2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0 }
+            },
+            Self::BounceOut => bounce(t),
+            Self::Steps(steps) => if t == 1.0 { 1.0 } else { (t * steps.max(1) as f32).floor() / steps.max(1) as f32 },
+            Self::CubicBezier(..) if t == 0.0 || t == 1.0 => t,
+            Self::CubicBezier(x1, y1, x2, y2) => {
+                let bezier = |t: f32, c1: f32, c2: f32| {
+                    let u = 1.0 - t;
+                    3.0*u*u*t*c1 + 3.0*u*t*t*c2 + t*t*t
+                };
+                // x is monotonic for x1, x2 within 0..1, bisect for the curve parameter
+                let (mut lo, mut hi) = (0.0, 1.0);
+                for _ in 0..24 {
+                    let mid = (lo + hi) / 2.0;
+                    if bezier(mid, x1, x2) < t { lo = mid } else { hi = mid }
+                }
+                bezier((lo + hi) / 2.0, y1, y2)
+            },
+        }
+    }
+}
+
+
+// tween from a start instant
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tween<T: Animatable> {
+    pub from: T,
+    pub to: T,
+    pub duration: Duration,
+    pub easing: Easing,
+    pub start: Instant,
+}
+
+impl<T: Animatable> Tween<T> {
+
+    pub fn new(from: T, to: T, duration: Duration, easing: Easing, start: Instant) -> Self {
+        Self { from, to, duration, easing, start }
+    }
+
+    pub fn progress(&self, instant: Instant) -> f32 {
+        if self.duration.is_zero() { return 1.0 }
+        (instant.saturating_duration_since(self.start).as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    // value for a normalized progress, e.g. from NormInterval::elapsed
+    pub fn value_at(&self, progress: f32) -> T { self.from.interpolate(self.to, self.easing.apply(progress)) }
+
+    pub fn value(&self, instant: Instant) -> T { self.value_at(self.progress(instant)) }
+
+    pub fn is_done(&self, instant: Instant) -> bool { self.progress(instant) >= 1.0 }
+
+    // retarget from the current value, keeping the duration
+    pub fn retarget(&mut self, to: T, instant: Instant) {
+        *self = Self { from: self.value(instant), to, start: instant, ..*self };
+    }
+}
+
+
+// keyframe track, easing applies to the segment leading into a key
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframes<T: Animatable> {
+    keys: Vec<(Duration, T, Easing)>,
+}
+
+impl<T: Animatable> Default for Keyframes<T> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<T: Animatable> Keyframes<T> {
+
+    pub fn new() -> Self { Self { keys: Vec::new() } }
+
+    pub fn with_key(mut self, time: Duration, value: T, easing: Easing) -> Self { self.add(time, value, easing); self }
+
+    // keeps keys ordered by time, replaces a key at the same time
+    pub fn add(&mut self, time: Duration, value: T, easing: Easing) {
+        match self.keys.binary_search_by(|(other, ..)| other.cmp(&time)) {
+            Ok(i) => self.keys[i] = (time, value, easing),
+            Err(i) => self.keys.insert(i, (time, value, easing)),
+        }
+    }
+
+    pub fn keys(&self) -> &[(Duration, T, Easing)] { &self.keys }
+
+    pub fn duration(&self) -> Duration { self.keys.last().map(|(time, ..)| *time).unwrap_or_default() }
+
+    // holds the first and last value outside of the track
+    pub fn sample(&self, time: Duration) -> Option<T> {
+
+        let i = self.keys.partition_point(|(other, ..)| *other <= time);
+
+        match (i.checked_sub(1).map(|i| self.keys[i]), self.keys.get(i).copied()) {
+            (Some((t0, v0, _)), Some((t1, v1, easing))) => {
+                let t = (time - t0).as_secs_f32() / (t1 - t0).as_secs_f32();
+                Some(v0.interpolate(v1, easing.apply(t)))
+            },
+            (Some((_, value, _)), None) | (None, Some((_, value, _))) => Some(value),
+            (None, None) => None,
+        }
+    }
+}
+
+
+// critically damped spring, approaches the target without overshoot
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring<T: Animatable> {
+    pub value: T,
+    pub velocity: T, // per second
+    pub target: T,
+    pub frequency: f32, // angular frequency, higher is faster
+    pub epsilon: f32, // settled below this distance and speed
+    last: Option<Instant>,
+}
+
+impl<T: Animatable> Spring<T> {
+
+    pub fn new(value: T, frequency: f32) -> Self {
+        Self { value, velocity: value.scale(0.0), target: value, frequency, epsilon: 1e-3, last: None }
+    }
+
+    // approximate time to settle for a frequency
+    pub fn with_settle_time(value: T, settle_time: Duration) -> Self {
+        Self::new(value, 6.6 / settle_time.as_secs_f32().max(f32::EPSILON))
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.value.diff(self.target).magnitude() < self.epsilon && self.velocity.magnitude() < self.epsilon
+    }
+
+    // exact solution of x'' = -w^2 (x - target) - 2w x'
+    pub fn step(&mut self, dt: Duration) {
+
+        if self.is_settled() {
+            self.value = self.target;
+            self.velocity = self.velocity.scale(0.0);
+            return;
+        }
+
+        let (w, t) = (self.frequency, dt.as_secs_f32());
+        let offset = self.value.diff(self.target);
+        let c = self.velocity.sum(offset.scale(w));
+        let decay = (-w * t).exp();
+
+        self.value = self.target.sum(offset.sum(c.scale(t)).scale(decay));
+        self.velocity = self.velocity.diff(c.scale(w * t)).scale(decay);
+    }
+
+    // advances by the time since the previous update, the first only sets the time
+    pub fn update(&mut self, instant: Instant) -> T {
+        if let Some(last) = self.last.replace(instant) {
+            self.step(instant.saturating_duration_since(last));
+        }
+        self.value
+    }
+
+    // forget the time of the last update, e.g. after a pause
+    pub fn reset_time(&mut self) { self.last = None }
+}
+
+
+// frame-pacing flow
+
+#[cfg(feature = "frame_pacing")]
+impl<T: Animatable> Tween<T> {
+    // value at the frame time, requests the next frame until done
+    pub fn frame_value<U: crate::EventLike>(&self, app_ctx: &mut crate::AppCtx<U>) -> T {
+        let frame_time = app_ctx.frame_time();
+        if !self.is_done(frame_time) { app_ctx.request_frame() }
+        self.value(frame_time)
+    }
+}
+
+#[cfg(feature = "frame_pacing")]
+impl<T: Animatable> Spring<T> {
+    // advances to the frame time, requests the next frame until settled
+    pub fn frame_update<U: crate::EventLike>(&mut self, app_ctx: &mut crate::AppCtx<U>) -> T {
+        let value = self.update(app_ctx.frame_time());
+        if self.is_settled() { self.reset_time() } else { app_ctx.request_frame() }
+        value
+    }
+}
+
+// driven by the app clock, no frames are requested while it is paused
+
+#[cfg(all(feature = "frame_pacing", feature = "app_clock"))]
+impl<T: Animatable> Tween<T> {
+    pub fn app_frame_value<U: crate::EventLike>(&self, app_ctx: &mut crate::AppCtx<U>) -> T {
+        let frame_time = app_ctx.app_frame_time();
+        if !self.is_done(frame_time) && !app_ctx.is_clock_paused() { app_ctx.request_frame() }
+        self.value(frame_time)
+    }
+}
+
+#[cfg(all(feature = "frame_pacing", feature = "app_clock"))]
+impl<T: Animatable> Spring<T> {
+    pub fn app_frame_update<U: crate::EventLike>(&mut self, app_ctx: &mut crate::AppCtx<U>) -> T {
+        let value = self.update(app_ctx.app_frame_time());
+        if self.is_settled() { self.reset_time() } else if !app_ctx.is_clock_paused() { app_ctx.request_frame() }
+        value
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn easing_endpoints() {
+        for easing in [
+            Easing::Linear, Easing::QuadInOut, Easing::CubicOut, Easing::SineInOut, Easing::ExpoInOut,
+            Easing::BackInOut, Easing::ElasticOut, Easing::BounceOut, Easing::Steps(4), Easing::CubicBezier(0.25, 0.1, 0.25, 1.0),
+        ] {
+            assert!(easing.apply(0.0).abs() < 1e-4, "{easing:?}");
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-4, "{easing:?}");
+        }
+        assert_eq!(Easing::QuadInOut.apply(0.5), 0.5);
+        assert_eq!(Easing::Steps(4).apply(0.6), 0.5);
+        assert!(Easing::BackIn.apply(0.2) < 0.0);
+    }
+
+    #[test]
+    fn keyframes_and_spring() {
+
+        let ms = Duration::from_millis;
+
+        let track = Keyframes::new()
+            .with_key(ms(100), [0.0, 10.0], Easing::Linear)
+            .with_key(ms(300), [10.0, 30.0], Easing::Linear);
+
+        assert_eq!(track.sample(ms(0)), Some([0.0, 10.0]));
+        assert_eq!(track.sample(ms(200)), Some([5.0, 20.0]));
+        assert_eq!(track.sample(ms(400)), Some([10.0, 30.0]));
+        assert_eq!(track.duration(), ms(300));
+
+        let mut spring = Spring::new(0.0f32, 20.0);
+        spring.target = 1.0;
+
+        let mut max: f32 = 0.0;
+        for _ in 0..120 {
+            spring.step(ms(16));
+            max = max.max(spring.value);
+        }
+
+        assert!(max <= 1.0); // no overshoot
+        assert!(spring.is_settled());
+    }
+}